@@ -1,13 +1,14 @@
 use crate::{
     ToJson,
     flaws::{Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId},
+    heuristics::{FlawKind, ResolverKind},
     solver::SolverError,
     solver_state::SolverState,
 };
 use linarith::Rational;
 use riddle::{
     core::Core,
-    env::{AtomId, BoolExpr, Env},
+    env::{Atom, AtomId, BoolExpr, Env},
     scope::{Predicate, get_predicate_by_path},
 };
 use serde_json::{Value, json};
@@ -36,6 +37,9 @@ impl Flaw for AtomFlaw {
     fn id(&self) -> FlawId {
         self.flw.id()
     }
+    fn kind(&self) -> FlawKind {
+        FlawKind::Atom
+    }
     fn phi(&self) -> VarId {
         self.flw.phi()
     }
@@ -108,6 +112,29 @@ impl ToJson for AtomFlaw {
     }
 }
 
+/// Returns, for each argument of the atom's predicate and of its ancestors, the equality between the argument of `atom` and that of `target`.
+pub(crate) fn arg_equalities(solver: &SolverState, atom: &Atom, target: &Atom) -> Vec<(String, Rc<BoolExpr>)> {
+    let mut eqs = Vec::new();
+    let mut pred_q: VecDeque<Rc<Predicate>> = VecDeque::new();
+    pred_q.push_back(atom.predicate());
+    while let Some(pred) = pred_q.pop_front() {
+        for (_, name) in pred.args() {
+            eqs.push((
+                name.to_string(),
+                Rc::new(BoolExpr::Eq {
+                    var_type: Rc::downgrade(&solver.bool_type()),
+                    left: atom.get(name).expect("Atom should have the argument"),
+                    right: target.get(name).expect("Target atom should have the argument"),
+                }),
+            ));
+        }
+        for super_pred in pred.parents() {
+            pred_q.push_back(get_predicate_by_path(pred.as_ref(), super_pred).expect("Predicate should exist"));
+        }
+    }
+    eqs
+}
+
 struct UnifyAtom {
     res: ResolverData,
     atom: AtomId,
@@ -119,6 +146,7 @@ struct UnifyAtom {
 impl UnifyAtom {
     fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, atom: AtomId, target: AtomId) -> Box<Self> {
         let solver = slv.upgrade().expect("Solver has been dropped");
+        solver.add_unification(rho, atom, target);
         Box::new(Self {
             res: ResolverData::new(slv, id, flaw, rho, Rational::from(1)),
             atom,
@@ -136,6 +164,9 @@ impl Resolver for UnifyAtom {
     fn id(&self) -> ResolverId {
         self.res.id()
     }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::UnifyAtom
+    }
     fn flaw(&self) -> FlawId {
         self.res.flaw()
     }
@@ -156,22 +187,7 @@ impl Resolver for UnifyAtom {
         // If rho is true, then the target atom must be active
         solver.sat.borrow_mut().add_clause(vec![neg(self.rho()), pos(solver.get_sigma(self.target))]).expect("Failed to add clause for UnifyAtom resolver");
 
-        let mut terms: Vec<Rc<BoolExpr>> = Vec::new();
-        let mut pred_q: VecDeque<Rc<Predicate>> = VecDeque::new();
-        pred_q.push_back(atom.predicate());
-        while let Some(pred) = pred_q.pop_front() {
-            for (_, name) in pred.args() {
-                terms.push(Rc::new(BoolExpr::Eq {
-                    var_type: Rc::downgrade(&solver.bool_type()),
-                    left: atom.get(name).expect("Atom should have the argument"),
-                    right: target.get(name).expect("Target atom should have the argument"),
-                }));
-            }
-            for super_pred in pred.parents() {
-                pred_q.push_back(get_predicate_by_path(pred.as_ref(), super_pred).expect("Predicate should exist"));
-            }
-        }
-
+        let terms = arg_equalities(&solver, &atom, &target).into_iter().map(|(_, eq)| eq).collect();
         if !solver.assert(Rc::new(BoolExpr::And { var_type: Rc::downgrade(&solver.bool_type()), terms })) {
             return Err(SolverError::RuntimeError("Failed to unify atoms due to a contradiction".into()));
         }
@@ -184,6 +200,9 @@ impl Resolver for UnifyAtom {
     fn ac_constraints(&self) -> Option<Vec<ac3rm::ConstraintId>> {
         Some(self.ac_constraints.borrow().clone())
     }
+    fn add_ac_constraint(&mut self, constraint: ac3rm::ConstraintId) {
+        self.ac_constraints.borrow_mut().push(constraint);
+    }
     fn lin_guard(&self) -> Option<linarith::GuardId> {
         Some(self.lin_guard)
     }
@@ -217,6 +236,9 @@ impl Resolver for ActivateFact {
     fn id(&self) -> ResolverId {
         self.res.id()
     }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::ActivateFact
+    }
     fn flaw(&self) -> FlawId {
         self.res.flaw()
     }
@@ -272,6 +294,9 @@ impl Resolver for ActivateGoal {
     fn id(&self) -> ResolverId {
         self.res.id()
     }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::ActivateGoal
+    }
     fn flaw(&self) -> FlawId {
         self.res.flaw()
     }
@@ -300,6 +325,9 @@ impl Resolver for ActivateGoal {
     fn ac_constraints(&self) -> Option<Vec<ac3rm::ConstraintId>> {
         Some(self.ac_constraints.borrow().clone())
     }
+    fn add_ac_constraint(&mut self, constraint: ac3rm::ConstraintId) {
+        self.ac_constraints.borrow_mut().push(constraint);
+    }
     fn lin_guard(&self) -> Option<linarith::GuardId> {
         Some(self.lin_guard)
     }
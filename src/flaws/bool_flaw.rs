@@ -0,0 +1,143 @@
+use crate::{
+    ToJson,
+    flaws::{Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId},
+    heuristics::{FlawKind, ResolverKind},
+    solver::SolverError,
+    solver_state::SolverState,
+};
+use linarith::Rational;
+use serde_json::{Value, json};
+use std::rc::{Rc, Weak};
+use watchsat::{Lit, VarId, neg, pos};
+
+/// A Boolean variable, whose value has to be chosen.
+pub(crate) struct BoolFlaw {
+    flw: FlawData,
+    lit: Lit,
+}
+
+impl BoolFlaw {
+    pub(crate) fn new(slv: Weak<SolverState>, id: FlawId, phi: VarId, cause: Option<ResolverId>, lit: Lit) -> Box<Self> {
+        Box::new(Self { flw: FlawData::new(slv, id, phi, cause.into_iter().collect()), lit })
+    }
+}
+
+impl Flaw for BoolFlaw {
+    fn solver(&self) -> Rc<SolverState> {
+        self.flw.solver()
+    }
+    fn id(&self) -> FlawId {
+        self.flw.id()
+    }
+    fn kind(&self) -> FlawKind {
+        FlawKind::Bool
+    }
+    fn phi(&self) -> VarId {
+        self.flw.phi()
+    }
+    fn causes(&self) -> Vec<ResolverId> {
+        self.flw.causes()
+    }
+    fn supports(&self) -> Vec<ResolverId> {
+        self.flw.supports()
+    }
+    fn add_support(&mut self, support_id: ResolverId) {
+        self.flw.add_support(support_id);
+    }
+    fn resolvers(&self) -> Vec<ResolverId> {
+        self.flw.resolvers()
+    }
+    fn is_expanded(&self) -> bool {
+        self.flw.is_expanded()
+    }
+
+    fn compute_resolvers(&mut self) {
+        let solver = self.solver();
+        // The negative value comes first, and is slightly cheaper, as in the default polarity of SAT solvers
+        for (val, intrinsic_cost) in [(false, Rational::new(1, 2)), (true, Rational::from(1))] {
+            let lit = if val { self.lit } else { !self.lit };
+            // The value cannot be used as `rho` if negative, hence it is made equivalent to a fresh variable
+            let rho = if lit == pos(lit.var()) {
+                lit.var()
+            } else {
+                let rho = solver.sat.borrow_mut().add_var();
+                let mut sat = solver.sat.borrow_mut();
+                sat.add_clause(vec![neg(rho), lit]).expect("Failed to add clause for negative literal");
+                sat.add_clause(vec![pos(rho), !lit]).expect("Failed to add clause for negative literal");
+                rho
+            };
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = BoolResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, val, intrinsic_cost);
+            solver.add_resolver(self, res);
+        }
+        self.flw.set_expanded();
+    }
+
+    fn add_resolver(&mut self, resolver_id: ResolverId) {
+        self.flw.add_resolver(resolver_id);
+    }
+
+    fn cost(&self) -> Rational {
+        self.flw.cost()
+    }
+    fn set_cost(&mut self, cost: Rational) {
+        self.flw.set_cost(cost);
+    }
+}
+
+impl ToJson for BoolFlaw {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "bool",
+            "lit": self.lit.to_string(),
+        })
+    }
+}
+
+struct BoolResolver {
+    res: ResolverData,
+    val: bool,
+}
+
+impl BoolResolver {
+    fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, val: bool, intrinsic_cost: Rational) -> Box<Self> {
+        Box::new(Self { res: ResolverData::new(slv, id, flaw, rho, intrinsic_cost), val })
+    }
+}
+
+impl Resolver for BoolResolver {
+    fn solver(&self) -> Rc<SolverState> {
+        self.res.solver()
+    }
+    fn id(&self) -> ResolverId {
+        self.res.id()
+    }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::BoolValue(self.val)
+    }
+    fn flaw(&self) -> FlawId {
+        self.res.flaw()
+    }
+    fn rho(&self) -> VarId {
+        self.res.rho()
+    }
+    fn intrinsic_cost(&self) -> Rational {
+        self.res.intrinsic_cost()
+    }
+
+    fn apply(&mut self) -> Result<(), SolverError> {
+        Ok(())
+    }
+    fn requirements(&self) -> Vec<FlawId> {
+        self.res.requirements()
+    }
+}
+
+impl ToJson for BoolResolver {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "val",
+            "val": self.val,
+        })
+    }
+}
@@ -1,13 +1,14 @@
 use crate::{
     ToJson,
     flaws::{Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId},
+    heuristics::{FlawKind, ResolverKind},
     solver::SolverError,
     solver_state::SolverState,
 };
 use linarith::Rational;
 use serde_json::{Value, json};
 use std::rc::{Rc, Weak};
-use watchsat::{Lit, VarId};
+use watchsat::{Lit, VarId, neg, pos};
 
 pub(crate) struct ClauseFlaw {
     flw: FlawData,
@@ -27,6 +28,9 @@ impl Flaw for ClauseFlaw {
     fn id(&self) -> FlawId {
         self.flw.id()
     }
+    fn kind(&self) -> FlawKind {
+        FlawKind::Clause
+    }
     fn phi(&self) -> VarId {
         self.flw.phi()
     }
@@ -47,10 +51,21 @@ impl Flaw for ClauseFlaw {
     }
 
     fn compute_resolvers(&mut self) {
+        let solver = self.solver();
         for lit in self.lits.clone() {
-            let res_id = ResolverId(self.solver().get_resolvers_len());
-            let res = ClauseResolver::new(self.flw.slv.clone(), res_id, self.id(), lit);
-            self.solver().add_resolver(self, res);
+            // A negative literal cannot be used as `rho`, hence it is made equivalent to a fresh variable
+            let rho = if lit == pos(lit.var()) {
+                lit.var()
+            } else {
+                let rho = solver.sat.borrow_mut().add_var();
+                let mut sat = solver.sat.borrow_mut();
+                sat.add_clause(vec![neg(rho), lit]).expect("Failed to add clause for negative literal");
+                sat.add_clause(vec![pos(rho), !lit]).expect("Failed to add clause for negative literal");
+                rho
+            };
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = ClauseResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, lit);
+            solver.add_resolver(self, res);
         }
         self.flw.set_expanded();
     }
@@ -82,8 +97,8 @@ struct ClauseResolver {
 }
 
 impl ClauseResolver {
-    fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, lit: Lit) -> Box<Self> {
-        Box::new(Self { res: ResolverData::new(slv, id, flaw, lit.var(), Rational::from(1)), lit })
+    fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, lit: Lit) -> Box<Self> {
+        Box::new(Self { res: ResolverData::new(slv, id, flaw, rho, Rational::from(1)), lit })
     }
 }
 
@@ -94,6 +109,9 @@ impl Resolver for ClauseResolver {
     fn id(&self) -> ResolverId {
         self.res.id()
     }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::ClauseLiteral
+    }
     fn flaw(&self) -> FlawId {
         self.res.flaw()
     }
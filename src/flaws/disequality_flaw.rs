@@ -0,0 +1,163 @@
+use crate::{
+    ToJson,
+    flaws::{Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId},
+    heuristics::{FlawKind, ResolverKind},
+    solver::SolverError,
+    solver_state::{SolverState, numeric_lin},
+};
+use linarith::Rational;
+use riddle::{
+    core::Core,
+    env::{BoolExpr, Slot},
+};
+use serde_json::{Value, json};
+use std::rc::{Rc, Weak};
+use watchsat::VarId;
+
+/// A disequality `left != right` between two arithmetic expressions.
+/// The resolvers force either `left < right` or `right < left`.
+pub(crate) struct DisequalityFlaw {
+    flw: FlawData,
+    left: Slot,
+    right: Slot,
+}
+
+impl DisequalityFlaw {
+    pub(crate) fn new(slv: Weak<SolverState>, id: FlawId, phi: VarId, cause: Option<ResolverId>, left: Slot, right: Slot) -> Box<Self> {
+        Box::new(Self { flw: FlawData::new(slv, id, phi, cause.into_iter().collect()), left, right })
+    }
+}
+
+impl Flaw for DisequalityFlaw {
+    fn solver(&self) -> Rc<SolverState> {
+        self.flw.solver()
+    }
+    fn id(&self) -> FlawId {
+        self.flw.id()
+    }
+    fn kind(&self) -> FlawKind {
+        FlawKind::Disequality
+    }
+    fn phi(&self) -> VarId {
+        self.flw.phi()
+    }
+    fn causes(&self) -> Vec<ResolverId> {
+        self.flw.causes()
+    }
+    fn supports(&self) -> Vec<ResolverId> {
+        self.flw.supports()
+    }
+    fn add_support(&mut self, support_id: ResolverId) {
+        self.flw.add_support(support_id);
+    }
+    fn resolvers(&self) -> Vec<ResolverId> {
+        self.flw.resolvers()
+    }
+    fn is_expanded(&self) -> bool {
+        self.flw.is_expanded()
+    }
+
+    fn compute_resolvers(&mut self) {
+        let solver = self.solver();
+        for (left, right) in [(self.left.clone(), self.right.clone()), (self.right.clone(), self.left.clone())] {
+            let rho = solver.sat.borrow_mut().add_var();
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = LessResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, left, right);
+            solver.add_resolver(self, res);
+        }
+        self.flw.set_expanded();
+    }
+
+    fn add_resolver(&mut self, resolver_id: ResolverId) {
+        self.flw.add_resolver(resolver_id);
+    }
+
+    fn cost(&self) -> Rational {
+        self.flw.cost()
+    }
+    fn set_cost(&mut self, cost: Rational) {
+        self.flw.set_cost(cost);
+    }
+}
+
+impl ToJson for DisequalityFlaw {
+    fn to_json(&self) -> Value {
+        let solver = self.solver();
+        json!({
+            "kind": "disequality",
+            "left": solver.arith_val(&numeric_lin(&self.left)).to_json(),
+            "right": solver.arith_val(&numeric_lin(&self.right)).to_json(),
+        })
+    }
+}
+
+/// Forces the `left` expression to be strictly less than the `right` one.
+struct LessResolver {
+    res: ResolverData,
+    left: Slot,
+    right: Slot,
+    lin_guard: linarith::GuardId,
+}
+
+impl LessResolver {
+    fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, left: Slot, right: Slot) -> Box<Self> {
+        let solver = slv.upgrade().expect("Solver has been dropped");
+        Box::new(Self {
+            res: ResolverData::new(slv, id, flaw, rho, Rational::from(1)),
+            left,
+            right,
+            lin_guard: solver.lin.borrow_mut().add_guard(),
+        })
+    }
+}
+
+impl Resolver for LessResolver {
+    fn solver(&self) -> Rc<SolverState> {
+        self.res.solver()
+    }
+    fn id(&self) -> ResolverId {
+        self.res.id()
+    }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::Less
+    }
+    fn flaw(&self) -> FlawId {
+        self.res.flaw()
+    }
+    fn rho(&self) -> VarId {
+        self.res.rho()
+    }
+    fn intrinsic_cost(&self) -> Rational {
+        self.res.intrinsic_cost()
+    }
+
+    fn apply(&mut self) -> Result<(), SolverError> {
+        let solver = self.solver();
+        if !solver.assert(Rc::new(BoolExpr::Lt {
+            var_type: Rc::downgrade(&solver.bool_type()),
+            left: self.left.clone(),
+            right: self.right.clone(),
+        })) {
+            return Err(SolverError::RuntimeError("Failed to order the expressions of a disequality due to a contradiction".into()));
+        }
+        Ok(())
+    }
+    fn requirements(&self) -> Vec<FlawId> {
+        self.res.requirements()
+    }
+
+    fn lin_guard(&self) -> Option<linarith::GuardId> {
+        Some(self.lin_guard)
+    }
+}
+
+impl ToJson for LessResolver {
+    fn to_json(&self) -> Value {
+        let solver = self.solver();
+        json!({
+            "kind": "less",
+            "left": solver.arith_val(&numeric_lin(&self.left)).to_json(),
+            "right": solver.arith_val(&numeric_lin(&self.right)).to_json(),
+        })
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    ToJson,
+    flaws::{Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId},
+    heuristics::{FlawKind, ResolverKind},
+    solver::SolverError,
+    solver_state::SolverState,
+};
+use linarith::Rational;
+use riddle::language::Disjunction;
+use serde_json::{Value, json};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+use watchsat::VarId;
+
+pub(crate) struct DisjunctionFlaw {
+    flw: FlawData,
+    disjunction: Rc<Disjunction>,
+}
+
+impl DisjunctionFlaw {
+    pub(crate) fn new(slv: Weak<SolverState>, id: FlawId, phi: VarId, cause: Option<ResolverId>, disjunction: Disjunction) -> Box<Self> {
+        Box::new(Self {
+            flw: FlawData::new(slv, id, phi, cause.into_iter().collect()),
+            disjunction: Rc::new(disjunction),
+        })
+    }
+}
+
+impl Flaw for DisjunctionFlaw {
+    fn solver(&self) -> Rc<SolverState> {
+        self.flw.solver()
+    }
+    fn id(&self) -> FlawId {
+        self.flw.id()
+    }
+    fn kind(&self) -> FlawKind {
+        FlawKind::Disjunction
+    }
+    fn phi(&self) -> VarId {
+        self.flw.phi()
+    }
+    fn causes(&self) -> Vec<ResolverId> {
+        self.flw.causes()
+    }
+    fn supports(&self) -> Vec<ResolverId> {
+        self.flw.supports()
+    }
+    fn add_support(&mut self, support_id: ResolverId) {
+        self.flw.add_support(support_id);
+    }
+    fn resolvers(&self) -> Vec<ResolverId> {
+        self.flw.resolvers()
+    }
+    fn is_expanded(&self) -> bool {
+        self.flw.is_expanded()
+    }
+
+    fn compute_resolvers(&mut self) {
+        let solver = self.solver();
+        for (disjunct, _) in self.disjunction.disjuncts().iter().enumerate() {
+            let rho = solver.sat.borrow_mut().add_var();
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = DisjunctResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, self.disjunction.clone(), disjunct);
+            solver.add_resolver(self, res);
+        }
+        self.flw.set_expanded();
+    }
+
+    fn add_resolver(&mut self, resolver_id: ResolverId) {
+        self.flw.add_resolver(resolver_id);
+    }
+
+    fn cost(&self) -> Rational {
+        self.flw.cost()
+    }
+    fn set_cost(&mut self, cost: Rational) {
+        self.flw.set_cost(cost);
+    }
+}
+
+impl ToJson for DisjunctionFlaw {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "disjunction",
+            "disjuncts": self.disjunction.disjuncts().len(),
+        })
+    }
+}
+
+/// Executes the statements of one of the disjuncts, under its own `rho` and linear guard.
+struct DisjunctResolver {
+    res: ResolverData,
+    disjunction: Rc<Disjunction>,
+    disjunct: usize,
+    ac_constraints: RefCell<Vec<ac3rm::ConstraintId>>,
+    lin_guard: linarith::GuardId,
+}
+
+impl DisjunctResolver {
+    fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, disjunction: Rc<Disjunction>, disjunct: usize) -> Box<Self> {
+        let solver = slv.upgrade().expect("Solver has been dropped");
+        let intrinsic_cost = disjunction.disjuncts()[disjunct].cost();
+        Box::new(Self {
+            res: ResolverData::new(slv, id, flaw, rho, intrinsic_cost),
+            disjunction,
+            disjunct,
+            ac_constraints: RefCell::new(vec![]),
+            lin_guard: solver.lin.borrow_mut().add_guard(),
+        })
+    }
+}
+
+impl Resolver for DisjunctResolver {
+    fn solver(&self) -> Rc<SolverState> {
+        self.res.solver()
+    }
+    fn id(&self) -> ResolverId {
+        self.res.id()
+    }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::Disjunct
+    }
+    fn flaw(&self) -> FlawId {
+        self.res.flaw()
+    }
+    fn rho(&self) -> VarId {
+        self.res.rho()
+    }
+    fn intrinsic_cost(&self) -> Rational {
+        self.res.intrinsic_cost()
+    }
+
+    fn apply(&mut self) -> Result<(), SolverError> {
+        self.disjunction.disjuncts()[self.disjunct].execute().map_err(|e| SolverError::RuntimeError(format!("Failed to execute disjunct: {}", e)))
+    }
+    fn requirements(&self) -> Vec<FlawId> {
+        self.res.requirements()
+    }
+    fn add_requirement(&mut self, flaw_id: FlawId) {
+        self.res.add_requirement(flaw_id);
+    }
+
+    fn ac_constraints(&self) -> Option<Vec<ac3rm::ConstraintId>> {
+        Some(self.ac_constraints.borrow().clone())
+    }
+    fn add_ac_constraint(&mut self, constraint: ac3rm::ConstraintId) {
+        self.ac_constraints.borrow_mut().push(constraint);
+    }
+    fn lin_guard(&self) -> Option<linarith::GuardId> {
+        Some(self.lin_guard)
+    }
+}
+
+impl ToJson for DisjunctResolver {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "disjunct",
+            "disjunct": self.disjunct,
+        })
+    }
+}
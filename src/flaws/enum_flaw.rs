@@ -1,6 +1,7 @@
 use crate::{
     ToJson,
     flaws::{Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId},
+    heuristics::{FlawKind, ResolverKind},
     objects::EnumVar,
     solver::SolverError,
     solver_state::SolverState,
@@ -37,6 +38,9 @@ impl Flaw for EnumFlaw {
     fn id(&self) -> FlawId {
         self.flw.id()
     }
+    fn kind(&self) -> FlawKind {
+        FlawKind::Enum
+    }
     fn phi(&self) -> VarId {
         self.flw.phi()
     }
@@ -128,6 +132,9 @@ impl Resolver for EnumResolver {
     fn id(&self) -> ResolverId {
         self.res.id()
     }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::EnumValue(self.val)
+    }
     fn flaw(&self) -> FlawId {
         self.res.flaw()
     }
@@ -0,0 +1,168 @@
+use crate::{
+    ToJson,
+    flaws::{Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId},
+    heuristics::{FlawKind, ResolverKind},
+    objects::ArithVar,
+    solver::SolverError,
+    solver_state::SolverState,
+};
+use linarith::{Lin, Rational};
+use riddle::{
+    core::Core,
+    env::{BoolExpr, Slot},
+};
+use serde_json::{Value, json};
+use std::rc::{Rc, Weak};
+use watchsat::VarId;
+
+/// An integer variable whose current value is not integral.
+/// The resolvers split its domain around the value, as in branch and bound.
+pub(crate) struct IntegralityFlaw {
+    flw: FlawData,
+    var: linarith::VarId,
+    val: Rational,
+}
+
+impl IntegralityFlaw {
+    pub(crate) fn new(slv: Weak<SolverState>, id: FlawId, phi: VarId, var: linarith::VarId, val: Rational) -> Box<Self> {
+        Box::new(Self { flw: FlawData::new(slv, id, phi, Vec::new()), var, val })
+    }
+}
+
+impl Flaw for IntegralityFlaw {
+    fn solver(&self) -> Rc<SolverState> {
+        self.flw.solver()
+    }
+    fn id(&self) -> FlawId {
+        self.flw.id()
+    }
+    fn kind(&self) -> FlawKind {
+        FlawKind::Integrality
+    }
+    fn phi(&self) -> VarId {
+        self.flw.phi()
+    }
+    fn causes(&self) -> Vec<ResolverId> {
+        self.flw.causes()
+    }
+    fn supports(&self) -> Vec<ResolverId> {
+        self.flw.supports()
+    }
+    fn add_support(&mut self, support_id: ResolverId) {
+        self.flw.add_support(support_id);
+    }
+    fn resolvers(&self) -> Vec<ResolverId> {
+        self.flw.resolvers()
+    }
+    fn is_expanded(&self) -> bool {
+        self.flw.is_expanded()
+    }
+
+    fn compute_resolvers(&mut self) {
+        let solver = self.solver();
+        for (bound, upper) in [(self.val.floor(), true), (self.val.ceil(), false)] {
+            let rho = solver.sat.borrow_mut().add_var();
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = BranchResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, self.var, bound, upper);
+            solver.add_resolver(self, res);
+        }
+        self.flw.set_expanded();
+    }
+
+    fn add_resolver(&mut self, resolver_id: ResolverId) {
+        self.flw.add_resolver(resolver_id);
+    }
+
+    fn cost(&self) -> Rational {
+        self.flw.cost()
+    }
+    fn set_cost(&mut self, cost: Rational) {
+        self.flw.set_cost(cost);
+    }
+}
+
+impl ToJson for IntegralityFlaw {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "integrality",
+            "var": format!("{:?}", self.var),
+            "val": self.val.to_json(),
+        })
+    }
+}
+
+/// Bounds the integer variable either from above, i.e., `var <= bound`, or from below, i.e., `var >= bound`.
+struct BranchResolver {
+    res: ResolverData,
+    var: linarith::VarId,
+    bound: Rational,
+    upper: bool,
+    lin_guard: linarith::GuardId,
+}
+
+impl BranchResolver {
+    fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, var: linarith::VarId, bound: Rational, upper: bool) -> Box<Self> {
+        let solver = slv.upgrade().expect("Solver has been dropped");
+        Box::new(Self {
+            res: ResolverData::new(slv, id, flaw, rho, Rational::from(1)),
+            var,
+            bound,
+            upper,
+            lin_guard: solver.lin.borrow_mut().add_guard(),
+        })
+    }
+}
+
+impl Resolver for BranchResolver {
+    fn solver(&self) -> Rc<SolverState> {
+        self.res.solver()
+    }
+    fn id(&self) -> ResolverId {
+        self.res.id()
+    }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::Branch
+    }
+    fn flaw(&self) -> FlawId {
+        self.res.flaw()
+    }
+    fn rho(&self) -> VarId {
+        self.res.rho()
+    }
+    fn intrinsic_cost(&self) -> Rational {
+        self.res.intrinsic_cost()
+    }
+
+    fn apply(&mut self) -> Result<(), SolverError> {
+        let solver = self.solver();
+        // The variable is constrained by the linear solver, which has to see the branch as well
+        if !solver.release_to_lin(&Lin::from(self.var)) {
+            return Err(SolverError::RuntimeError("Failed to branch on integer variable due to a contradiction".into()));
+        }
+        let var = Slot::Primitive(Rc::new(ArithVar::new(solver.int_type(), Lin::from(self.var))));
+        let bound = Slot::Primitive(Rc::new(ArithVar::new(solver.int_type(), Lin::from(self.bound))));
+        let (left, right) = if self.upper { (var, bound) } else { (bound, var) };
+        if !solver.assert(Rc::new(BoolExpr::Leq { var_type: Rc::downgrade(&solver.bool_type()), left, right })) {
+            return Err(SolverError::RuntimeError("Failed to branch on integer variable due to a contradiction".into()));
+        }
+        Ok(())
+    }
+    fn requirements(&self) -> Vec<FlawId> {
+        self.res.requirements()
+    }
+
+    fn lin_guard(&self) -> Option<linarith::GuardId> {
+        Some(self.lin_guard)
+    }
+}
+
+impl ToJson for BranchResolver {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "branch",
+            "var": format!("{:?}", self.var),
+            "bound": self.bound.to_json(),
+            "upper": self.upper,
+        })
+    }
+}
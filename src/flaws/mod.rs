@@ -1,4 +1,9 @@
-use crate::{ToJson, solver::SolverError, solver_state::SolverState};
+use crate::{
+    ToJson,
+    heuristics::{FlawKind, ResolverKind},
+    solver::SolverError,
+    solver_state::SolverState,
+};
 use linarith::Rational;
 use serde_json::{Value, json};
 use std::{
@@ -9,10 +14,19 @@ use std::{
 use watchsat::{LBool, VarId};
 
 pub(crate) mod atom_flaw;
+pub(crate) mod bool_flaw;
 pub(crate) mod clause_flaw;
+pub(crate) mod disequality_flaw;
+pub(crate) mod disjunction_flaw;
 pub(crate) mod enum_flaw;
-
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) mod integrality_flaw;
+pub(crate) mod peak_flaw;
+pub(crate) mod product_flaw;
+pub(crate) mod propositional_state_flaw;
+pub(crate) mod state_variable_flaw;
+pub(crate) mod string_flaw;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct FlawId(pub(crate) usize);
 
 impl Deref for FlawId {
@@ -29,7 +43,7 @@ impl fmt::Display for FlawId {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ResolverId(pub(crate) usize);
 
 impl Deref for ResolverId {
@@ -49,6 +63,7 @@ impl fmt::Display for ResolverId {
 pub trait Flaw: ToJson {
     fn solver(&self) -> Rc<SolverState>;
     fn id(&self) -> FlawId;
+    fn kind(&self) -> FlawKind;
     fn phi(&self) -> VarId;
     fn causes(&self) -> Vec<ResolverId>;
     fn supports(&self) -> Vec<ResolverId>;
@@ -64,6 +79,7 @@ pub trait Flaw: ToJson {
 pub trait Resolver: ToJson {
     fn solver(&self) -> Rc<SolverState>;
     fn id(&self) -> ResolverId;
+    fn kind(&self) -> ResolverKind;
     fn flaw(&self) -> FlawId;
     fn rho(&self) -> VarId;
     fn intrinsic_cost(&self) -> Rational;
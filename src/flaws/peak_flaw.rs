@@ -0,0 +1,90 @@
+use crate::{
+    ToJson,
+    flaws::{Flaw, FlawData, FlawId, ResolverId, state_variable_flaw::OrderResolver},
+    heuristics::FlawKind,
+    solver_state::SolverState,
+};
+use linarith::Rational;
+use riddle::env::AtomId;
+use serde_json::{Value, json};
+use std::rc::{Rc, Weak};
+use watchsat::VarId;
+
+/// A set of atoms of a resource which, if overlapping, would violate the resource's bounds.
+/// Each resolver orders one of the given pairs of atoms, on the time points at which they take effect for consumable resources.
+pub(crate) struct PeakFlaw {
+    flw: FlawData,
+    kind: FlawKind,
+    atoms: Vec<AtomId>,
+    orderings: Vec<(AtomId, AtomId)>,
+}
+
+impl PeakFlaw {
+    pub(crate) fn new(slv: Weak<SolverState>, id: FlawId, phi: VarId, kind: FlawKind, atoms: Vec<AtomId>, orderings: Vec<(AtomId, AtomId)>) -> Box<Self> {
+        Box::new(Self { flw: FlawData::new(slv, id, phi, Vec::new()), kind, atoms, orderings })
+    }
+}
+
+impl Flaw for PeakFlaw {
+    fn solver(&self) -> Rc<SolverState> {
+        self.flw.solver()
+    }
+    fn id(&self) -> FlawId {
+        self.flw.id()
+    }
+    fn kind(&self) -> FlawKind {
+        self.kind
+    }
+    fn phi(&self) -> VarId {
+        self.flw.phi()
+    }
+    fn causes(&self) -> Vec<ResolverId> {
+        self.flw.causes()
+    }
+    fn supports(&self) -> Vec<ResolverId> {
+        self.flw.supports()
+    }
+    fn add_support(&mut self, support_id: ResolverId) {
+        self.flw.add_support(support_id);
+    }
+    fn resolvers(&self) -> Vec<ResolverId> {
+        self.flw.resolvers()
+    }
+    fn is_expanded(&self) -> bool {
+        self.flw.is_expanded()
+    }
+
+    fn compute_resolvers(&mut self) {
+        let solver = self.solver();
+        for (before, after) in self.orderings.clone() {
+            let rho = solver.sat.borrow_mut().add_var();
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = match self.kind {
+                FlawKind::ConsumableResource => OrderResolver::on_effects(self.flw.slv.clone(), res_id, self.id(), rho, before, after),
+                _ => OrderResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, before, after),
+            };
+            solver.add_resolver(self, res);
+        }
+        self.flw.set_expanded();
+    }
+
+    fn add_resolver(&mut self, resolver_id: ResolverId) {
+        self.flw.add_resolver(resolver_id);
+    }
+
+    fn cost(&self) -> Rational {
+        self.flw.cost()
+    }
+    fn set_cost(&mut self, cost: Rational) {
+        self.flw.set_cost(cost);
+    }
+}
+
+impl ToJson for PeakFlaw {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": self.kind.to_string(),
+            "atoms": self.atoms.iter().map(|atom| format!("{}", atom)).collect::<Vec<_>>(),
+        })
+    }
+}
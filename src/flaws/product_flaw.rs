@@ -0,0 +1,202 @@
+use crate::{
+    ToJson,
+    flaws::{Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId},
+    heuristics::{FlawKind, ResolverKind},
+    objects::ArithVar,
+    solver::SolverError,
+    solver_state::SolverState,
+};
+use linarith::{Lin, Rational};
+use riddle::{
+    core::Core,
+    env::{BoolExpr, Slot},
+};
+use serde_json::{Value, json};
+use std::rc::{Rc, Weak};
+use watchsat::VarId;
+
+/// The bounds `[x_lb, x_ub, y_lb, y_ub]` of the factors of a product.
+pub(crate) type Bounds = [Rational; 4];
+
+/// A bilinear term `z == x * y`, being `z` a fresh variable.
+#[derive(Clone)]
+pub(crate) struct Product {
+    pub(crate) x: Lin,
+    pub(crate) y: Lin,
+    pub(crate) z: Lin,
+}
+
+impl Product {
+    /// Returns the McCormick envelope of the product within the given bounds of its factors, as `left <= right` pairs.
+    pub(crate) fn envelope(&self, bounds: &Bounds) -> [(Lin, Lin); 4] {
+        let [x_lb, x_ub, y_lb, y_ub] = *bounds;
+        [(self.corner(x_lb, y_lb), self.z.clone()), (self.corner(x_ub, y_ub), self.z.clone()), (self.z.clone(), self.corner(x_ub, y_lb)), (self.z.clone(), self.corner(x_lb, y_ub))]
+    }
+
+    /// Returns `a * y + b * x - a * b`, which bounds the product from above or below depending on the corner `(a, b)` of the bounds.
+    fn corner(&self, a: Rational, b: Rational) -> Lin {
+        let mut lin = &self.y * a;
+        lin += &(&self.x * b);
+        lin += &Lin::from(-(a * b));
+        lin
+    }
+}
+
+/// A product whose current value is not exact.
+/// The resolvers bisect the domain of the widest factor, relaxing the product within each half through the McCormick envelope.
+pub(crate) struct ProductFlaw {
+    flw: FlawData,
+    product: Product,
+    bounds: Bounds,
+}
+
+impl ProductFlaw {
+    pub(crate) fn new(slv: Weak<SolverState>, id: FlawId, phi: VarId, product: Product, bounds: Bounds) -> Box<Self> {
+        Box::new(Self { flw: FlawData::new(slv, id, phi, Vec::new()), product, bounds })
+    }
+}
+
+impl Flaw for ProductFlaw {
+    fn solver(&self) -> Rc<SolverState> {
+        self.flw.solver()
+    }
+    fn id(&self) -> FlawId {
+        self.flw.id()
+    }
+    fn kind(&self) -> FlawKind {
+        FlawKind::Product
+    }
+    fn phi(&self) -> VarId {
+        self.flw.phi()
+    }
+    fn causes(&self) -> Vec<ResolverId> {
+        self.flw.causes()
+    }
+    fn supports(&self) -> Vec<ResolverId> {
+        self.flw.supports()
+    }
+    fn add_support(&mut self, support_id: ResolverId) {
+        self.flw.add_support(support_id);
+    }
+    fn resolvers(&self) -> Vec<ResolverId> {
+        self.flw.resolvers()
+    }
+    fn is_expanded(&self) -> bool {
+        self.flw.is_expanded()
+    }
+
+    fn compute_resolvers(&mut self) {
+        let solver = self.solver();
+        let [x_lb, x_ub, y_lb, y_ub] = self.bounds;
+        let halves = if x_ub - x_lb >= y_ub - y_lb {
+            let mid = (x_lb + x_ub) / Rational::from(2);
+            [[x_lb, mid, y_lb, y_ub], [mid, x_ub, y_lb, y_ub]]
+        } else {
+            let mid = (y_lb + y_ub) / Rational::from(2);
+            [[x_lb, x_ub, y_lb, mid], [x_lb, x_ub, mid, y_ub]]
+        };
+        for bounds in halves {
+            let rho = solver.sat.borrow_mut().add_var();
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = BisectResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, self.product.clone(), bounds);
+            solver.add_resolver(self, res);
+        }
+        self.flw.set_expanded();
+    }
+
+    fn add_resolver(&mut self, resolver_id: ResolverId) {
+        self.flw.add_resolver(resolver_id);
+    }
+
+    fn cost(&self) -> Rational {
+        self.flw.cost()
+    }
+    fn set_cost(&mut self, cost: Rational) {
+        self.flw.set_cost(cost);
+    }
+}
+
+impl ToJson for ProductFlaw {
+    fn to_json(&self) -> Value {
+        let solver = self.solver();
+        json!({
+            "kind": "product",
+            "x": solver.arith_val(&self.product.x).to_json(),
+            "y": solver.arith_val(&self.product.y).to_json(),
+            "z": solver.arith_val(&self.product.z).to_json(),
+        })
+    }
+}
+
+/// Restricts the factors of the product within the given bounds, along with the McCormick envelope of the product within them.
+struct BisectResolver {
+    res: ResolverData,
+    product: Product,
+    bounds: Bounds,
+    lin_guard: linarith::GuardId,
+}
+
+impl BisectResolver {
+    fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, product: Product, bounds: Bounds) -> Box<Self> {
+        let solver = slv.upgrade().expect("Solver has been dropped");
+        Box::new(Self {
+            res: ResolverData::new(slv, id, flaw, rho, Rational::from(1)),
+            product,
+            bounds,
+            lin_guard: solver.lin.borrow_mut().add_guard(),
+        })
+    }
+}
+
+impl Resolver for BisectResolver {
+    fn solver(&self) -> Rc<SolverState> {
+        self.res.solver()
+    }
+    fn id(&self) -> ResolverId {
+        self.res.id()
+    }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::Bisect
+    }
+    fn flaw(&self) -> FlawId {
+        self.res.flaw()
+    }
+    fn rho(&self) -> VarId {
+        self.res.rho()
+    }
+    fn intrinsic_cost(&self) -> Rational {
+        self.res.intrinsic_cost()
+    }
+
+    fn apply(&mut self) -> Result<(), SolverError> {
+        let solver = self.solver();
+        let [x_lb, x_ub, y_lb, y_ub] = self.bounds;
+        let Product { x, y, .. } = self.product.clone();
+        let mut constraints = vec![(Lin::from(x_lb), x.clone()), (x, Lin::from(x_ub)), (Lin::from(y_lb), y.clone()), (y, Lin::from(y_ub))];
+        constraints.extend(self.product.envelope(&self.bounds));
+        for (left, right) in constraints {
+            let left = Slot::Primitive(Rc::new(ArithVar::new(solver.real_type(), left)));
+            let right = Slot::Primitive(Rc::new(ArithVar::new(solver.real_type(), right)));
+            if !solver.assert(Rc::new(BoolExpr::Leq { var_type: Rc::downgrade(&solver.bool_type()), left, right })) {
+                return Err(SolverError::RuntimeError("Failed to bisect the factors of a product due to a contradiction".into()));
+            }
+        }
+        Ok(())
+    }
+    fn requirements(&self) -> Vec<FlawId> {
+        self.res.requirements()
+    }
+
+    fn lin_guard(&self) -> Option<linarith::GuardId> {
+        Some(self.lin_guard)
+    }
+}
+
+impl ToJson for BisectResolver {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "bisect",
+            "bounds": self.bounds.iter().map(|bound| bound.to_json()).collect::<Vec<_>>(),
+        })
+    }
+}
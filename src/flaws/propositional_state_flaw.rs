@@ -0,0 +1,289 @@
+use crate::{
+    ToJson,
+    flaws::{
+        Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId,
+        atom_flaw::arg_equalities,
+        state_variable_flaw::{OrderResolver, SplitResolver, is_splittable},
+    },
+    heuristics::{FlawKind, ResolverKind},
+    solver::SolverError,
+    solver_state::SolverState,
+};
+use linarith::Rational;
+use riddle::{
+    core::Core,
+    env::{Atom, AtomId, BoolExpr},
+};
+use serde_json::{Value, json};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+use watchsat::VarId;
+
+/// The arguments which do not identify a fluent.
+const TEMPORAL_ARGS: [&str; 5] = ["start", "end", "duration", "at", "polarity"];
+
+/// Returns, for each argument identifying the fluent of `atom`, the equality between the argument of `atom` and that of `other`.
+pub(crate) fn fluent_equalities(solver: &SolverState, atom: &Atom, other: &Atom) -> Vec<(String, Rc<BoolExpr>)> {
+    arg_equalities(solver, atom, other).into_iter().filter(|(name, _)| !TEMPORAL_ARGS.contains(&name.as_str())).collect()
+}
+
+/// Two atoms of a propositional state which might overlap in time, either being two different positive fluents or the same fluent with opposite polarities.
+pub(crate) struct PropositionalStateFlaw {
+    flw: FlawData,
+    atom: AtomId,
+    other: AtomId,
+    /// Whether the two atoms have the same polarity, i.e., whether they are both positive.
+    same_polarity: bool,
+}
+
+impl PropositionalStateFlaw {
+    pub(crate) fn new(slv: Weak<SolverState>, id: FlawId, phi: VarId, atom: AtomId, other: AtomId, same_polarity: bool) -> Box<Self> {
+        Box::new(Self { flw: FlawData::new(slv, id, phi, Vec::new()), atom, other, same_polarity })
+    }
+}
+
+impl Flaw for PropositionalStateFlaw {
+    fn solver(&self) -> Rc<SolverState> {
+        self.flw.solver()
+    }
+    fn id(&self) -> FlawId {
+        self.flw.id()
+    }
+    fn kind(&self) -> FlawKind {
+        FlawKind::PropositionalState
+    }
+    fn phi(&self) -> VarId {
+        self.flw.phi()
+    }
+    fn causes(&self) -> Vec<ResolverId> {
+        self.flw.causes()
+    }
+    fn supports(&self) -> Vec<ResolverId> {
+        self.flw.supports()
+    }
+    fn add_support(&mut self, support_id: ResolverId) {
+        self.flw.add_support(support_id);
+    }
+    fn resolvers(&self) -> Vec<ResolverId> {
+        self.flw.resolvers()
+    }
+    fn is_expanded(&self) -> bool {
+        self.flw.is_expanded()
+    }
+
+    fn compute_resolvers(&mut self) {
+        let solver = self.solver();
+        for (before, after) in [(self.atom, self.other), (self.other, self.atom)] {
+            let rho = solver.sat.borrow_mut().add_var();
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = OrderResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, before, after);
+            solver.add_resolver(self, res);
+        }
+        let atom = solver.get_atom(self.atom).expect("Flaw's atom should exist");
+        let other = solver.get_atom(self.other).expect("Flaw's atom should exist");
+        if is_splittable(&atom, &other) {
+            let rho = solver.sat.borrow_mut().add_var();
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = SplitResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, self.atom, self.other);
+            solver.add_resolver(self, res);
+        }
+        if atom.predicate().name() == other.predicate().name() {
+            if self.same_polarity {
+                // Two overlapping positive atoms are allowed as long as they represent the same fluent
+                let rho = solver.sat.borrow_mut().add_var();
+                let res_id = ResolverId(solver.get_resolvers_len());
+                let res = MergeResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, self.atom, self.other);
+                solver.add_resolver(self, res);
+            } else {
+                // A negative atom can overlap a positive one as long as they represent different fluents
+                for (arg, _) in fluent_equalities(&solver, &atom, &other) {
+                    let rho = solver.sat.borrow_mut().add_var();
+                    let res_id = ResolverId(solver.get_resolvers_len());
+                    let res = DistinguishResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, self.atom, self.other, arg);
+                    solver.add_resolver(self, res);
+                }
+            }
+        }
+        self.flw.set_expanded();
+    }
+
+    fn add_resolver(&mut self, resolver_id: ResolverId) {
+        self.flw.add_resolver(resolver_id);
+    }
+
+    fn cost(&self) -> Rational {
+        self.flw.cost()
+    }
+    fn set_cost(&mut self, cost: Rational) {
+        self.flw.set_cost(cost);
+    }
+}
+
+impl ToJson for PropositionalStateFlaw {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "propositional-state",
+            "atoms": [format!("{}", self.atom), format!("{}", self.other)],
+            "same_polarity": self.same_polarity,
+        })
+    }
+}
+
+/// Makes two positive atoms represent the same fluent, by equating the arguments identifying their fluents.
+struct MergeResolver {
+    res: ResolverData,
+    atom: AtomId,
+    other: AtomId,
+    ac_constraints: RefCell<Vec<ac3rm::ConstraintId>>,
+    lin_guard: linarith::GuardId,
+}
+
+impl MergeResolver {
+    fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, atom: AtomId, other: AtomId) -> Box<Self> {
+        let solver = slv.upgrade().expect("Solver has been dropped");
+        Box::new(Self {
+            res: ResolverData::new(slv, id, flaw, rho, Rational::from(1)),
+            atom,
+            other,
+            ac_constraints: RefCell::new(vec![]),
+            lin_guard: solver.lin.borrow_mut().add_guard(),
+        })
+    }
+}
+
+impl Resolver for MergeResolver {
+    fn solver(&self) -> Rc<SolverState> {
+        self.res.solver()
+    }
+    fn id(&self) -> ResolverId {
+        self.res.id()
+    }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::Merge
+    }
+    fn flaw(&self) -> FlawId {
+        self.res.flaw()
+    }
+    fn rho(&self) -> VarId {
+        self.res.rho()
+    }
+    fn intrinsic_cost(&self) -> Rational {
+        self.res.intrinsic_cost()
+    }
+
+    fn apply(&mut self) -> Result<(), SolverError> {
+        let solver = self.solver();
+        let atom = solver.get_atom(self.atom).expect("Atom should exist");
+        let other = solver.get_atom(self.other).expect("Atom should exist");
+        let terms = fluent_equalities(&solver, &atom, &other).into_iter().map(|(_, eq)| eq).collect();
+        if !solver.assert(Rc::new(BoolExpr::And { var_type: Rc::downgrade(&solver.bool_type()), terms })) {
+            return Err(SolverError::RuntimeError("Failed to merge fluents due to a contradiction".into()));
+        }
+        Ok(())
+    }
+    fn requirements(&self) -> Vec<FlawId> {
+        self.res.requirements()
+    }
+
+    fn ac_constraints(&self) -> Option<Vec<ac3rm::ConstraintId>> {
+        Some(self.ac_constraints.borrow().clone())
+    }
+    fn add_ac_constraint(&mut self, constraint: ac3rm::ConstraintId) {
+        self.ac_constraints.borrow_mut().push(constraint);
+    }
+    fn lin_guard(&self) -> Option<linarith::GuardId> {
+        Some(self.lin_guard)
+    }
+}
+
+impl ToJson for MergeResolver {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "merge",
+            "atom": format!("{}", self.atom),
+            "other": format!("{}", self.other),
+        })
+    }
+}
+
+/// Makes two atoms represent different fluents, by forcing the given argument to differ.
+struct DistinguishResolver {
+    res: ResolverData,
+    atom: AtomId,
+    other: AtomId,
+    arg: String,
+    ac_constraints: RefCell<Vec<ac3rm::ConstraintId>>,
+    lin_guard: linarith::GuardId,
+}
+
+impl DistinguishResolver {
+    fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, atom: AtomId, other: AtomId, arg: String) -> Box<Self> {
+        let solver = slv.upgrade().expect("Solver has been dropped");
+        Box::new(Self {
+            res: ResolverData::new(slv, id, flaw, rho, Rational::from(1)),
+            atom,
+            other,
+            arg,
+            ac_constraints: RefCell::new(vec![]),
+            lin_guard: solver.lin.borrow_mut().add_guard(),
+        })
+    }
+}
+
+impl Resolver for DistinguishResolver {
+    fn solver(&self) -> Rc<SolverState> {
+        self.res.solver()
+    }
+    fn id(&self) -> ResolverId {
+        self.res.id()
+    }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::Distinguish
+    }
+    fn flaw(&self) -> FlawId {
+        self.res.flaw()
+    }
+    fn rho(&self) -> VarId {
+        self.res.rho()
+    }
+    fn intrinsic_cost(&self) -> Rational {
+        self.res.intrinsic_cost()
+    }
+
+    fn apply(&mut self) -> Result<(), SolverError> {
+        let solver = self.solver();
+        let atom = solver.get_atom(self.atom).expect("Atom should exist");
+        let other = solver.get_atom(self.other).expect("Atom should exist");
+        let (_, eq) = fluent_equalities(&solver, &atom, &other).into_iter().find(|(name, _)| *name == self.arg).expect("Atoms should have the argument");
+        if !solver.assert(Rc::new(BoolExpr::Not { var_type: Rc::downgrade(&solver.bool_type()), term: eq })) {
+            return Err(SolverError::RuntimeError("Failed to distinguish fluents due to a contradiction".into()));
+        }
+        Ok(())
+    }
+    fn requirements(&self) -> Vec<FlawId> {
+        self.res.requirements()
+    }
+
+    fn ac_constraints(&self) -> Option<Vec<ac3rm::ConstraintId>> {
+        Some(self.ac_constraints.borrow().clone())
+    }
+    fn add_ac_constraint(&mut self, constraint: ac3rm::ConstraintId) {
+        self.ac_constraints.borrow_mut().push(constraint);
+    }
+    fn lin_guard(&self) -> Option<linarith::GuardId> {
+        Some(self.lin_guard)
+    }
+}
+
+impl ToJson for DistinguishResolver {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "distinguish",
+            "atom": format!("{}", self.atom),
+            "other": format!("{}", self.other),
+            "arg": self.arg,
+        })
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    ToJson,
+    flaws::{Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId},
+    heuristics::{FlawKind, ResolverKind},
+    objects::EnumVar,
+    solver::SolverError,
+    solver_state::{SolverState, TAU},
+    timelines::{atom_bounds, consumable_resource::effect_time},
+};
+use linarith::Rational;
+use riddle::{
+    core::Core,
+    env::{Atom, AtomId, BoolExpr, Slot},
+};
+use serde_json::{Value, json};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+use watchsat::VarId;
+
+/// Two atoms of a state variable which might overlap in time.
+pub(crate) struct StateVariableFlaw {
+    flw: FlawData,
+    atom: AtomId,
+    other: AtomId,
+}
+
+impl StateVariableFlaw {
+    pub(crate) fn new(slv: Weak<SolverState>, id: FlawId, phi: VarId, atom: AtomId, other: AtomId) -> Box<Self> {
+        Box::new(Self { flw: FlawData::new(slv, id, phi, Vec::new()), atom, other })
+    }
+}
+
+impl Flaw for StateVariableFlaw {
+    fn solver(&self) -> Rc<SolverState> {
+        self.flw.solver()
+    }
+    fn id(&self) -> FlawId {
+        self.flw.id()
+    }
+    fn kind(&self) -> FlawKind {
+        FlawKind::StateVariable
+    }
+    fn phi(&self) -> VarId {
+        self.flw.phi()
+    }
+    fn causes(&self) -> Vec<ResolverId> {
+        self.flw.causes()
+    }
+    fn supports(&self) -> Vec<ResolverId> {
+        self.flw.supports()
+    }
+    fn add_support(&mut self, support_id: ResolverId) {
+        self.flw.add_support(support_id);
+    }
+    fn resolvers(&self) -> Vec<ResolverId> {
+        self.flw.resolvers()
+    }
+    fn is_expanded(&self) -> bool {
+        self.flw.is_expanded()
+    }
+
+    fn compute_resolvers(&mut self) {
+        let solver = self.solver();
+        for (before, after) in [(self.atom, self.other), (self.other, self.atom)] {
+            let rho = solver.sat.borrow_mut().add_var();
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = OrderResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, before, after);
+            solver.add_resolver(self, res);
+        }
+        if is_splittable(&solver.get_atom(self.atom).expect("Flaw's atom should exist"), &solver.get_atom(self.other).expect("Flaw's atom should exist")) {
+            let rho = solver.sat.borrow_mut().add_var();
+            let res_id = ResolverId(solver.get_resolvers_len());
+            let res = SplitResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, self.atom, self.other);
+            solver.add_resolver(self, res);
+        }
+        self.flw.set_expanded();
+    }
+
+    fn add_resolver(&mut self, resolver_id: ResolverId) {
+        self.flw.add_resolver(resolver_id);
+    }
+
+    fn cost(&self) -> Rational {
+        self.flw.cost()
+    }
+    fn set_cost(&mut self, cost: Rational) {
+        self.flw.set_cost(cost);
+    }
+}
+
+impl ToJson for StateVariableFlaw {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "state-variable",
+            "atoms": [format!("{}", self.atom), format!("{}", self.other)],
+        })
+    }
+}
+
+/// Whether either of the two atoms might be placed on a different object.
+pub(crate) fn is_splittable(atom: &Atom, other: &Atom) -> bool {
+    [atom.get(TAU), other.get(TAU)].iter().any(|tau| matches!(tau, Some(Slot::Primitive(var)) if var.clone().as_any().downcast_ref::<EnumVar>().is_some()))
+}
+
+/// Forces the `before` atom to end before the `after` atom starts or, if `on_effects`, to take effect before the `after` atom does.
+pub(crate) struct OrderResolver {
+    res: ResolverData,
+    before: AtomId,
+    after: AtomId,
+    on_effects: bool,
+    lin_guard: linarith::GuardId,
+}
+
+impl OrderResolver {
+    pub(crate) fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, before: AtomId, after: AtomId) -> Box<Self> {
+        let solver = slv.upgrade().expect("Solver has been dropped");
+        Box::new(Self {
+            res: ResolverData::new(slv, id, flaw, rho, Rational::from(1)),
+            before,
+            after,
+            on_effects: false,
+            lin_guard: solver.lin.borrow_mut().add_guard(),
+        })
+    }
+
+    /// Orders the time points at which the changes of a consumable resource take effect, rather than the whole atoms.
+    pub(crate) fn on_effects(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, before: AtomId, after: AtomId) -> Box<Self> {
+        let mut res = Self::new(slv, id, flaw, rho, before, after);
+        res.on_effects = true;
+        res
+    }
+}
+
+impl Resolver for OrderResolver {
+    fn solver(&self) -> Rc<SolverState> {
+        self.res.solver()
+    }
+    fn id(&self) -> ResolverId {
+        self.res.id()
+    }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::Order
+    }
+    fn flaw(&self) -> FlawId {
+        self.res.flaw()
+    }
+    fn rho(&self) -> VarId {
+        self.res.rho()
+    }
+    fn intrinsic_cost(&self) -> Rational {
+        self.res.intrinsic_cost()
+    }
+
+    fn apply(&mut self) -> Result<(), SolverError> {
+        let solver = self.solver();
+        let before = solver.get_atom(self.before).expect("Atom should exist");
+        let after = solver.get_atom(self.after).expect("Atom should exist");
+        let (before_end, after_start) = if self.on_effects {
+            (effect_time(&before).expect("Atom should change a consumable resource"), effect_time(&after).expect("Atom should change a consumable resource"))
+        } else {
+            (atom_bounds(&before).expect("Atom should be temporal").1, atom_bounds(&after).expect("Atom should be temporal").0)
+        };
+        if !solver.assert(Rc::new(BoolExpr::Leq { var_type: Rc::downgrade(&solver.bool_type()), left: before_end, right: after_start })) {
+            return Err(SolverError::RuntimeError("Failed to order atoms due to a contradiction".into()));
+        }
+        Ok(())
+    }
+    fn requirements(&self) -> Vec<FlawId> {
+        self.res.requirements()
+    }
+
+    fn lin_guard(&self) -> Option<linarith::GuardId> {
+        Some(self.lin_guard)
+    }
+}
+
+impl ToJson for OrderResolver {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "order",
+            "before": format!("{}", self.before),
+            "after": format!("{}", self.after),
+        })
+    }
+}
+
+/// Places the two atoms on different objects.
+pub(crate) struct SplitResolver {
+    res: ResolverData,
+    atom: AtomId,
+    other: AtomId,
+    ac_constraints: RefCell<Vec<ac3rm::ConstraintId>>,
+}
+
+impl SplitResolver {
+    pub(crate) fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, atom: AtomId, other: AtomId) -> Box<Self> {
+        Box::new(Self {
+            res: ResolverData::new(slv, id, flaw, rho, Rational::from(1)),
+            atom,
+            other,
+            ac_constraints: RefCell::new(vec![]),
+        })
+    }
+}
+
+impl Resolver for SplitResolver {
+    fn solver(&self) -> Rc<SolverState> {
+        self.res.solver()
+    }
+    fn id(&self) -> ResolverId {
+        self.res.id()
+    }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::Split
+    }
+    fn flaw(&self) -> FlawId {
+        self.res.flaw()
+    }
+    fn rho(&self) -> VarId {
+        self.res.rho()
+    }
+    fn intrinsic_cost(&self) -> Rational {
+        self.res.intrinsic_cost()
+    }
+
+    fn apply(&mut self) -> Result<(), SolverError> {
+        let solver = self.solver();
+        let atom = solver.get_atom(self.atom).expect("Atom should exist");
+        let other = solver.get_atom(self.other).expect("Atom should exist");
+        let eq = Rc::new(BoolExpr::Eq {
+            var_type: Rc::downgrade(&solver.bool_type()),
+            left: atom.get(TAU).expect("Atom should have a tau"),
+            right: other.get(TAU).expect("Atom should have a tau"),
+        });
+        if !solver.assert(Rc::new(BoolExpr::Not { var_type: Rc::downgrade(&solver.bool_type()), term: eq })) {
+            return Err(SolverError::RuntimeError("Failed to split atoms due to a contradiction".into()));
+        }
+        Ok(())
+    }
+    fn requirements(&self) -> Vec<FlawId> {
+        self.res.requirements()
+    }
+
+    fn ac_constraints(&self) -> Option<Vec<ac3rm::ConstraintId>> {
+        Some(self.ac_constraints.borrow().clone())
+    }
+    fn add_ac_constraint(&mut self, constraint: ac3rm::ConstraintId) {
+        self.ac_constraints.borrow_mut().push(constraint);
+    }
+}
+
+impl ToJson for SplitResolver {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "split",
+            "atom": format!("{}", self.atom),
+            "other": format!("{}", self.other),
+        })
+    }
+}
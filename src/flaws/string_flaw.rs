@@ -0,0 +1,151 @@
+use crate::{
+    ToJson,
+    flaws::{Flaw, FlawData, FlawId, Resolver, ResolverData, ResolverId},
+    heuristics::{FlawKind, ResolverKind},
+    objects::StringVar,
+    solver::SolverError,
+    solver_state::SolverState,
+};
+use linarith::Rational;
+use serde_json::{Value, json};
+use std::rc::{Rc, Weak};
+use watchsat::VarId;
+
+/// A string variable, whose value has to be chosen among the string literals in its domain.
+pub(crate) struct StringFlaw {
+    flw: FlawData,
+    var: Rc<StringVar>,
+}
+
+impl StringFlaw {
+    pub(crate) fn new(slv: Weak<SolverState>, id: FlawId, phi: VarId, cause: Option<ResolverId>, var: Rc<StringVar>) -> Box<Self> {
+        Box::new(Self { flw: FlawData::new(slv, id, phi, cause.into_iter().collect()), var })
+    }
+}
+
+impl Flaw for StringFlaw {
+    fn solver(&self) -> Rc<SolverState> {
+        self.flw.solver()
+    }
+    fn id(&self) -> FlawId {
+        self.flw.id()
+    }
+    fn kind(&self) -> FlawKind {
+        FlawKind::String
+    }
+    fn phi(&self) -> VarId {
+        self.flw.phi()
+    }
+    fn causes(&self) -> Vec<ResolverId> {
+        self.flw.causes()
+    }
+    fn supports(&self) -> Vec<ResolverId> {
+        self.flw.supports()
+    }
+    fn add_support(&mut self, support_id: ResolverId) {
+        self.flw.add_support(support_id);
+    }
+    fn resolvers(&self) -> Vec<ResolverId> {
+        self.flw.resolvers()
+    }
+    fn is_expanded(&self) -> bool {
+        self.flw.is_expanded()
+    }
+
+    fn compute_resolvers(&mut self) {
+        let solver = self.solver();
+        let vals = solver.ac.borrow().val(self.var.var());
+        let num_vals = vals.len();
+        for val in vals {
+            let res_id = ResolverId(self.solver().get_resolvers_len());
+            let rho = solver.sat.borrow_mut().add_var();
+            let res = StringResolver::new(self.flw.slv.clone(), res_id, self.id(), rho, self.var.clone(), val, Rational::new(1, num_vals as i64));
+            solver.add_resolver(self, res);
+            // The variable might have other listeners, e.g., the one of an enum flaw, which setting a listener would replace
+            solver.link_domain_value(self.var.var(), val, rho);
+        }
+
+        self.flw.set_expanded();
+    }
+
+    fn add_resolver(&mut self, resolver_id: ResolverId) {
+        self.flw.add_resolver(resolver_id);
+    }
+
+    fn cost(&self) -> Rational {
+        self.flw.cost()
+    }
+    fn set_cost(&mut self, cost: Rational) {
+        self.flw.set_cost(cost);
+    }
+}
+
+impl ToJson for StringFlaw {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "string",
+            "var": format!("{:?}", self.var.var.get()),
+        })
+    }
+}
+
+/// Assigns one of the string literals to a string variable.
+struct StringResolver {
+    res: ResolverData,
+    var: Rc<StringVar>,
+    val: i32,
+    ac_constraints: Vec<ac3rm::ConstraintId>,
+}
+
+impl StringResolver {
+    fn new(slv: Weak<SolverState>, id: ResolverId, flaw: FlawId, rho: VarId, var: Rc<StringVar>, val: i32, intrinsic_cost: Rational) -> Box<Self> {
+        Box::new(Self {
+            res: ResolverData::new(slv, id, flaw, rho, intrinsic_cost),
+            var,
+            val,
+            ac_constraints: Vec::new(),
+        })
+    }
+}
+
+impl Resolver for StringResolver {
+    fn solver(&self) -> Rc<SolverState> {
+        self.res.solver()
+    }
+    fn id(&self) -> ResolverId {
+        self.res.id()
+    }
+    fn kind(&self) -> ResolverKind {
+        ResolverKind::StringValue(self.val)
+    }
+    fn flaw(&self) -> FlawId {
+        self.res.flaw()
+    }
+    fn rho(&self) -> VarId {
+        self.res.rho()
+    }
+    fn intrinsic_cost(&self) -> Rational {
+        self.res.intrinsic_cost()
+    }
+
+    fn apply(&mut self) -> Result<(), SolverError> {
+        self.ac_constraints.push(self.solver().ac.borrow_mut().new_constraint(ac3rm::Constraint::Set(self.var.var(), self.val)));
+        Ok(())
+    }
+    fn requirements(&self) -> Vec<FlawId> {
+        self.res.requirements()
+    }
+
+    fn ac_constraints(&self) -> Option<Vec<ac3rm::ConstraintId>> {
+        Some(self.ac_constraints.clone())
+    }
+}
+
+impl ToJson for StringResolver {
+    fn to_json(&self) -> Value {
+        json!({
+            "kind": "string",
+            "val": self.val,
+        })
+    }
+}
@@ -0,0 +1,198 @@
+pub use crate::flaws::{FlawId, ResolverId};
+use linarith::Rational;
+use std::{cmp::Ordering, fmt};
+
+/// How the costs of a resolver's requirements are combined into the cost of the resolver.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CostHeuristic {
+    /// The most expensive requirement (h_max). Admissible, but it underestimates goals with many subgoals.
+    #[default]
+    Max,
+    /// The sum of the requirements' costs (h_add). Subgoals shared among requirements are counted more than once.
+    Add,
+    /// The sum of the intrinsic costs of the cheapest resolvers in the relaxed subgraph, counting each flaw once (h_ff).
+    FastForward,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FlawKind {
+    Atom,
+    Enum,
+    String,
+    Bool,
+    Clause,
+    Disjunction,
+    StateVariable,
+    ReusableResource,
+    ConsumableResource,
+    PropositionalState,
+    Integrality,
+    Disequality,
+    Product,
+}
+
+impl fmt::Display for FlawKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlawKind::Atom => write!(f, "atom"),
+            FlawKind::Enum => write!(f, "enum"),
+            FlawKind::String => write!(f, "string"),
+            FlawKind::Bool => write!(f, "bool"),
+            FlawKind::Clause => write!(f, "clause"),
+            FlawKind::Disjunction => write!(f, "disjunction"),
+            FlawKind::StateVariable => write!(f, "state-variable"),
+            FlawKind::ReusableResource => write!(f, "reusable-resource"),
+            FlawKind::ConsumableResource => write!(f, "consumable-resource"),
+            FlawKind::PropositionalState => write!(f, "propositional-state"),
+            FlawKind::Integrality => write!(f, "integrality"),
+            FlawKind::Disequality => write!(f, "disequality"),
+            FlawKind::Product => write!(f, "product"),
+        }
+    }
+}
+
+/// A snapshot of an active flaw, as seen by a [`FlawSelector`].
+#[derive(Clone, Debug)]
+pub struct FlawInfo {
+    pub id: FlawId,
+    pub kind: FlawKind,
+    pub cost: Rational,
+    /// The number of resolvers separating the flaw from the problem's root.
+    pub depth: usize,
+    /// The number of resolvers which have not been ruled out yet.
+    pub live_resolvers: usize,
+}
+
+/// Chooses the next flaw to resolve among the active ones.
+///
+/// The candidates are sorted by increasing flaw id, i.e., by creation order.
+pub trait FlawSelector: Send {
+    fn select_flaw(&mut self, flaws: &[FlawInfo]) -> Option<FlawId>;
+}
+
+/// Selects the flaw with the highest estimated cost. This is the default strategy.
+#[derive(Default)]
+pub struct MostExpensiveFlaw;
+
+impl FlawSelector for MostExpensiveFlaw {
+    fn select_flaw(&mut self, flaws: &[FlawInfo]) -> Option<FlawId> {
+        flaws.iter().rev().max_by_key(|flaw| flaw.cost).map(|flaw| flaw.id)
+    }
+}
+
+/// Selects the flaw with the fewest live resolvers, breaking ties by the highest cost.
+#[derive(Default)]
+pub struct FailFirst;
+
+impl FlawSelector for FailFirst {
+    fn select_flaw(&mut self, flaws: &[FlawInfo]) -> Option<FlawId> {
+        flaws.iter().min_by(|a, b| a.live_resolvers.cmp(&b.live_resolvers).then(b.cost.cmp(&a.cost))).map(|flaw| flaw.id)
+    }
+}
+
+/// Selects the flaws in the order they have been created.
+#[derive(Default)]
+pub struct Fifo;
+
+impl FlawSelector for Fifo {
+    fn select_flaw(&mut self, flaws: &[FlawInfo]) -> Option<FlawId> {
+        flaws.first().map(|flaw| flaw.id)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ResolverKind {
+    UnifyAtom,
+    ActivateGoal,
+    ActivateFact,
+    /// Assigns the given object to an object variable.
+    EnumValue(i32),
+    /// Assigns the string literal with the given code to a string variable.
+    StringValue(i32),
+    /// Assigns the given value to a Boolean variable.
+    BoolValue(bool),
+    ClauseLiteral,
+    /// Executes one of the disjuncts of a disjunction.
+    Disjunct,
+    /// Forces an atom to end before another one starts.
+    Order,
+    /// Places two atoms on different objects.
+    Split,
+    /// Makes two atoms represent the same fluent.
+    Merge,
+    /// Makes two atoms represent different fluents.
+    Distinguish,
+    /// Bounds an integer variable around a non-integral value.
+    Branch,
+    /// Forces an arithmetic expression to be strictly less than another one.
+    Less,
+    /// Restricts a factor of a product to half of its domain.
+    Bisect,
+}
+
+/// A snapshot of a live resolver, as seen by a [`ResolverSelector`].
+#[derive(Clone, Debug)]
+pub struct ResolverInfo {
+    pub id: ResolverId,
+    pub kind: ResolverKind,
+    pub intrinsic_cost: Rational,
+    /// The intrinsic cost combined with the cost of the resolver's requirements.
+    pub cost: Rational,
+}
+
+/// Chooses the resolver to apply to the selected flaw.
+///
+/// The candidates are the resolvers of the flaw which have not been ruled out yet, sorted by increasing resolver id.
+pub trait ResolverSelector: Send {
+    fn select_resolver(&mut self, flaw: &FlawInfo, resolvers: &[ResolverInfo]) -> Option<ResolverId>;
+}
+
+/// Selects the resolver with the lowest estimated cost. This is the default strategy.
+#[derive(Default)]
+pub struct CheapestResolver;
+
+impl ResolverSelector for CheapestResolver {
+    fn select_resolver(&mut self, _flaw: &FlawInfo, resolvers: &[ResolverInfo]) -> Option<ResolverId> {
+        resolvers.iter().min_by_key(|res| res.cost).map(|res| res.id)
+    }
+}
+
+/// Selects the cheapest unification, if any, falling back to the cheapest resolver otherwise.
+#[derive(Default)]
+pub struct PreferUnification;
+
+impl ResolverSelector for PreferUnification {
+    fn select_resolver(&mut self, _flaw: &FlawInfo, resolvers: &[ResolverInfo]) -> Option<ResolverId> {
+        resolvers.iter().min_by_key(|res| (res.kind != ResolverKind::UnifyAtom, res.cost)).map(|res| res.id)
+    }
+}
+
+/// Selects the activation of the atom, if still possible, falling back to the cheapest resolver otherwise.
+#[derive(Default)]
+pub struct PreferActivation;
+
+impl ResolverSelector for PreferActivation {
+    fn select_resolver(&mut self, _flaw: &FlawInfo, resolvers: &[ResolverInfo]) -> Option<ResolverId> {
+        resolvers.iter().min_by_key(|res| (!matches!(res.kind, ResolverKind::ActivateGoal | ResolverKind::ActivateFact), res.cost)).map(|res| res.id)
+    }
+}
+
+/// Orders the values of the object variables through the given comparator, selecting the cheapest resolver for any other flaw.
+pub struct ValueOrdering<F: FnMut(i32, i32) -> Ordering + Send> {
+    compare: F,
+}
+
+impl<F: FnMut(i32, i32) -> Ordering + Send> ValueOrdering<F> {
+    pub fn new(compare: F) -> Self {
+        Self { compare }
+    }
+}
+
+impl<F: FnMut(i32, i32) -> Ordering + Send> ResolverSelector for ValueOrdering<F> {
+    fn select_resolver(&mut self, flaw: &FlawInfo, resolvers: &[ResolverInfo]) -> Option<ResolverId> {
+        if flaw.kind != FlawKind::Enum {
+            return CheapestResolver.select_resolver(flaw, resolvers);
+        }
+        resolvers.iter().filter_map(|res| if let ResolverKind::EnumValue(val) = res.kind { Some((res.id, val)) } else { None }).min_by(|(_, a), (_, b)| (self.compare)(*a, *b)).map(|(id, _)| id)
+    }
+}
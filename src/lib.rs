@@ -1,10 +1,15 @@
 use serde_json::Value;
 
+pub mod heuristics;
+pub mod solution;
 pub mod solver;
+pub mod statistics;
 
 mod flaws;
 mod objects;
 mod solver_state;
+mod stn;
+mod timelines;
 
 pub trait ToJson {
     fn to_json(&self) -> Value;
@@ -1,10 +1,11 @@
 use linarith::Lin;
 use riddle::{
-    env::Var,
+    env::{ObjectId, Var},
     scope::{BoolType, StringType, Type},
 };
 use std::{
     any::Any,
+    cell::OnceCell,
     rc::{Rc, Weak},
 };
 use watchsat::Lit;
@@ -56,12 +57,17 @@ impl Var for ArithVar {
 #[derive(Debug)]
 pub struct StringVar {
     var_type: Weak<StringType>,
-    pub(crate) value: String,
+    /// The variable whose domain holds the codes of the allowed strings, created at the next solve over the literals read so far.
+    pub(crate) var: OnceCell<ac3rm::VarId>,
 }
 
 impl StringVar {
-    pub(crate) fn new(var_type: Rc<StringType>, value: String) -> Self {
-        Self { var_type: Rc::downgrade(&var_type), value }
+    pub(crate) fn new(var_type: Rc<StringType>, var: Option<ac3rm::VarId>) -> Self {
+        Self { var_type: Rc::downgrade(&var_type), var: var.map(OnceCell::from).unwrap_or_default() }
+    }
+
+    pub(crate) fn var(&self) -> ac3rm::VarId {
+        *self.var.get().expect("String variable should have a domain")
     }
 }
 
@@ -79,11 +85,13 @@ impl Var for StringVar {
 pub struct EnumVar {
     var_type: Weak<dyn Type>,
     pub(crate) var: ac3rm::VarId,
+    /// The objects in the initial domain of the variable.
+    pub(crate) instances: Vec<ObjectId>,
 }
 
 impl EnumVar {
-    pub(crate) fn new(var_type: Rc<dyn Type>, var: ac3rm::VarId) -> Self {
-        Self { var_type: Rc::downgrade(&var_type), var }
+    pub(crate) fn new(var_type: Rc<dyn Type>, var: ac3rm::VarId, instances: Vec<ObjectId>) -> Self {
+        Self { var_type: Rc::downgrade(&var_type), var, instances }
     }
 }
 
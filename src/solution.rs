@@ -0,0 +1,82 @@
+use crate::ToJson;
+use linarith::Rational;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use watchsat::LBool;
+
+/// The value of a RiDDLe variable in the current state of the solver.
+#[derive(Clone, Debug, PartialEq)]
+pub enum VarValue {
+    Bool(LBool),
+    /// The bounds of an `int` or `real` expression, together with the value currently assigned by the linear solver.
+    Arith {
+        lb: Rational,
+        ub: Rational,
+        val: Rational,
+    },
+    /// The bounds of a field accessed through an object variable which still allows several objects.
+    Bounds {
+        lb: Rational,
+        ub: Rational,
+    },
+    /// The ids of the objects which are still allowed by an object variable.
+    Enum(Vec<usize>),
+    String(String),
+    /// The strings which are still allowed by a string variable.
+    Strings(Vec<String>),
+    Object(usize),
+}
+
+impl ToJson for VarValue {
+    fn to_json(&self) -> Value {
+        match self {
+            VarValue::Bool(val) => json!({ "type": "bool", "val": val.to_json() }),
+            VarValue::Arith { lb, ub, val } => json!({ "type": "arith", "lb": lb.to_json(), "ub": ub.to_json(), "val": val.to_json() }),
+            VarValue::Bounds { lb, ub } => json!({ "type": "bounds", "lb": lb.to_json(), "ub": ub.to_json() }),
+            VarValue::Enum(vals) => json!({ "type": "enum", "vals": vals }),
+            VarValue::String(val) => json!({ "type": "string", "val": val }),
+            VarValue::Strings(vals) => json!({ "type": "strings", "vals": vals }),
+            VarValue::Object(id) => json!({ "type": "object", "val": id }),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AtomSolution {
+    pub id: usize,
+    pub predicate: String,
+    pub fact: bool,
+    pub args: BTreeMap<String, VarValue>,
+}
+
+impl ToJson for AtomSolution {
+    fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "predicate": self.predicate,
+            "fact": self.fact,
+            "args": self.args.iter().map(|(name, val)| (name.clone(), val.to_json())).collect::<serde_json::Map<_, _>>(),
+        })
+    }
+}
+
+/// The plan found by the solver.
+#[derive(Clone, Debug, Default)]
+pub struct Solution {
+    /// The atoms which are part of the plan and have not been unified with other atoms.
+    pub atoms: Vec<AtomSolution>,
+    /// The pairs `(atom, target)` of atoms which have been unified, by id.
+    pub unifications: Vec<(usize, usize)>,
+    /// The global variables declared in the RiDDLe problem, excluding those of the prelude, e.g., `origin` and `horizon`.
+    pub globals: BTreeMap<String, VarValue>,
+}
+
+impl ToJson for Solution {
+    fn to_json(&self) -> Value {
+        json!({
+            "atoms": self.atoms.iter().map(|atom| atom.to_json()).collect::<Vec<_>>(),
+            "unifications": self.unifications.iter().map(|(atom, target)| json!({ "atom": atom, "target": target })).collect::<Vec<_>>(),
+            "globals": self.globals.iter().map(|(name, val)| (name.clone(), val.to_json())).collect::<serde_json::Map<_, _>>(),
+        })
+    }
+}
@@ -1,8 +1,18 @@
 use crate::flaws::ResolverId;
+use crate::heuristics::{CostHeuristic, FlawSelector, ResolverSelector};
+use crate::solution::{Solution, VarValue};
 use crate::solver_state::SolverState;
+use crate::statistics::Statistics;
 use crate::{ToJson, flaws::FlawId};
 use linarith::Rational;
 use serde_json::Value;
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Instant,
+};
 use tokio::sync::{broadcast, mpsc, oneshot};
 use watchsat::{LBool, VarId};
 
@@ -10,7 +20,14 @@ type CommandResult<T> = oneshot::Sender<Result<T, SolverError>>;
 
 enum SolverCommand {
     ReadRiDDle(String, CommandResult<()>),
-    Solve(CommandResult<()>),
+    Solve(SolveLimits, CommandResult<()>),
+    SetFlawSelector(Box<dyn FlawSelector>, CommandResult<()>),
+    SetResolverSelector(Box<dyn ResolverSelector>, CommandResult<()>),
+    SetCostHeuristic(CostHeuristic, CommandResult<()>),
+    SetProductTolerance(Rational, CommandResult<()>),
+    Statistics(CommandResult<Statistics>),
+    Solution(CommandResult<Solution>),
+    ValueOf(String, CommandResult<VarValue>),
     ToJson(CommandResult<Value>),
 }
 
@@ -18,6 +35,38 @@ enum SolverCommand {
 pub enum SolverError {
     RuntimeError(String),
     Inconsistent,
+    /// The deadline has passed, or the flaw/resolver budget has been exhausted.
+    Timeout,
+    Cancelled,
+}
+
+/// A flag which can be raised from any task to stop an ongoing solve.
+#[derive(Clone, Default)]
+pub struct CancellationHandle(Arc<AtomicBool>);
+
+impl CancellationHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The limits within which a solve must end. Unset limits are not enforced.
+#[derive(Clone, Default)]
+pub struct SolveLimits {
+    pub deadline: Option<Instant>,
+    /// The maximum number of flaws created by the solve.
+    pub max_flaws: Option<usize>,
+    /// The maximum number of resolvers created by the solve.
+    pub max_resolvers: Option<usize>,
+    pub cancellation: Option<CancellationHandle>,
 }
 
 #[derive(Clone)]
@@ -62,7 +111,7 @@ impl Solver {
                             let _ = responder.send(Err(e));
                         }
                     },
-                    SolverCommand::Solve(responder) => match state.solve() {
+                    SolverCommand::Solve(limits, responder) => match state.solve(limits) {
                         Ok(_) => {
                             let _ = responder.send(Ok(()));
                         }
@@ -70,6 +119,31 @@ impl Solver {
                             let _ = responder.send(Err(e));
                         }
                     },
+                    SolverCommand::SetFlawSelector(selector, responder) => {
+                        state.set_flaw_selector(selector);
+                        let _ = responder.send(Ok(()));
+                    }
+                    SolverCommand::SetResolverSelector(selector, responder) => {
+                        state.set_resolver_selector(selector);
+                        let _ = responder.send(Ok(()));
+                    }
+                    SolverCommand::SetCostHeuristic(heuristic, responder) => {
+                        state.set_cost_heuristic(heuristic);
+                        let _ = responder.send(Ok(()));
+                    }
+                    SolverCommand::SetProductTolerance(tolerance, responder) => {
+                        state.set_product_tolerance(tolerance);
+                        let _ = responder.send(Ok(()));
+                    }
+                    SolverCommand::Statistics(responder) => {
+                        let _ = responder.send(Ok(state.statistics()));
+                    }
+                    SolverCommand::Solution(responder) => {
+                        let _ = responder.send(Ok(state.solution()));
+                    }
+                    SolverCommand::ValueOf(path, responder) => {
+                        let _ = responder.send(state.value_of(&path));
+                    }
                     SolverCommand::ToJson(responder) => {
                         let json = state.to_json();
                         let _ = responder.send(Ok(json));
@@ -87,8 +161,59 @@ impl Solver {
     }
 
     pub async fn solve(&self) -> Result<(), SolverError> {
+        self.solve_with_limits(SolveLimits::default()).await
+    }
+
+    /// Solves the problem, failing with [`SolverError::Timeout`] or [`SolverError::Cancelled`] as soon as one of the limits is reached.
+    /// The solver's state is left as it was when the solve stopped.
+    pub async fn solve_with_limits(&self, limits: SolveLimits) -> Result<(), SolverError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.tx_cmd.send(SolverCommand::Solve(limits, reply_tx)).await.map_err(|_| SolverError::Inconsistent)?;
+        reply_rx.await.map_err(|_| SolverError::Inconsistent)?
+    }
+
+    pub async fn set_flaw_selector(&self, selector: impl FlawSelector + 'static) -> Result<(), SolverError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.tx_cmd.send(SolverCommand::SetFlawSelector(Box::new(selector), reply_tx)).await.map_err(|_| SolverError::Inconsistent)?;
+        reply_rx.await.map_err(|_| SolverError::Inconsistent)?
+    }
+
+    pub async fn set_resolver_selector(&self, selector: impl ResolverSelector + 'static) -> Result<(), SolverError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.tx_cmd.send(SolverCommand::SetResolverSelector(Box::new(selector), reply_tx)).await.map_err(|_| SolverError::Inconsistent)?;
+        reply_rx.await.map_err(|_| SolverError::Inconsistent)?
+    }
+
+    pub async fn set_cost_heuristic(&self, heuristic: CostHeuristic) -> Result<(), SolverError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.tx_cmd.send(SolverCommand::SetCostHeuristic(heuristic, reply_tx)).await.map_err(|_| SolverError::Inconsistent)?;
+        reply_rx.await.map_err(|_| SolverError::Inconsistent)?
+    }
+
+    /// Sets the largest error between a non-linear product and the product of its factors which is considered exact.
+    pub async fn set_product_tolerance(&self, tolerance: Rational) -> Result<(), SolverError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.tx_cmd.send(SolverCommand::SetProductTolerance(tolerance, reply_tx)).await.map_err(|_| SolverError::Inconsistent)?;
+        reply_rx.await.map_err(|_| SolverError::Inconsistent)?
+    }
+
+    pub async fn statistics(&self) -> Result<Statistics, SolverError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.tx_cmd.send(SolverCommand::Statistics(reply_tx)).await.map_err(|_| SolverError::Inconsistent)?;
+        reply_rx.await.map_err(|_| SolverError::Inconsistent)?
+    }
+
+    /// Extracts the plan from the solver's current state. Meaningful once [`Solver::solve`] has succeeded.
+    pub async fn solution(&self) -> Result<Solution, SolverError> {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        self.tx_cmd.send(SolverCommand::Solution(reply_tx)).await.map_err(|_| SolverError::Inconsistent)?;
+        reply_rx.await.map_err(|_| SolverError::Inconsistent)?
+    }
+
+    /// Returns the current value of the variable at the given path, e.g., `x0` or `b3.weight`.
+    pub async fn value_of(&self, path: &str) -> Result<VarValue, SolverError> {
         let (reply_tx, reply_rx) = oneshot::channel();
-        self.tx_cmd.send(SolverCommand::Solve(reply_tx)).await.map_err(|_| SolverError::Inconsistent)?;
+        self.tx_cmd.send(SolverCommand::ValueOf(path.to_string(), reply_tx)).await.map_err(|_| SolverError::Inconsistent)?;
         reply_rx.await.map_err(|_| SolverError::Inconsistent)?
     }
 
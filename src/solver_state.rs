@@ -1,8 +1,24 @@
 use crate::{
     ToJson,
-    flaws::{Flaw, FlawId, Resolver, ResolverId, atom_flaw::AtomFlaw, clause_flaw::ClauseFlaw, enum_flaw::EnumFlaw},
+    flaws::{
+        Flaw, FlawId, Resolver, ResolverId,
+        atom_flaw::AtomFlaw,
+        bool_flaw::BoolFlaw,
+        clause_flaw::ClauseFlaw,
+        disequality_flaw::DisequalityFlaw,
+        disjunction_flaw::DisjunctionFlaw,
+        enum_flaw::EnumFlaw,
+        integrality_flaw::IntegralityFlaw,
+        product_flaw::{Bounds, Product, ProductFlaw},
+        string_flaw::StringFlaw,
+    },
+    heuristics::{CheapestResolver, CostHeuristic, FlawInfo, FlawKind, FlawSelector, MostExpensiveFlaw, ResolverInfo, ResolverKind, ResolverSelector},
     objects::{ArithVar, BoolVar, EnumVar, StringVar},
-    solver::{SolverError, SolverEvent},
+    solution::{AtomSolution, Solution, VarValue},
+    solver::{SolveLimits, SolverError, SolverEvent},
+    statistics::Statistics,
+    stn::{ContingentLink, Dist, Stn},
+    timelines::{Timeline, consumable_resource::ConsumableResource, propositional_state::PropositionalState, reusable_resource::ReusableResource, state_variable::StateVariable},
 };
 use linarith::{Lin, Rational};
 use riddle::{
@@ -10,40 +26,84 @@ use riddle::{
     core::{CommonCore, Core},
     env::{Atom, AtomId, BoolExpr, Env, Object, ObjectId, Slot, Var},
     language::Disjunction,
-    scope::{Class, Field, Function, Predicate, Scope, Type, arith_type},
+    scope::{Class, Field, Function, Predicate, Scope, Type, arith_type, get_predicate_by_path},
 };
 use serde_json::{Value, json};
 use std::{
-    cell::RefCell,
-    collections::{HashMap, HashSet, VecDeque},
+    cell::{Cell, RefCell},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     rc::{Rc, Weak},
+    time::Instant,
 };
 use tokio::sync::broadcast;
 use tracing::{info, trace, warn};
 use watchsat::{FALSE_LIT, LBool, Lit, TRUE_LIT, VarId, neg, pos};
 
+pub(crate) const TAU: &str = "tau";
+
 pub struct SolverState {
     core: Rc<CommonCore>,
     slv: Weak<SolverState>,
     pub sat: RefCell<watchsat::Engine>,
     prop_q: RefCell<VecDeque<Lit>>,
+    learnts: RefCell<Vec<Vec<Lit>>>,
+    /// Explained once the SAT engine has returned, since its listeners cannot borrow it.
+    conflicts: RefCell<Vec<(Lit, Conflict)>>,
     pub ac: RefCell<ac3rm::Engine>,
     pub lin: RefCell<linarith::Engine>,
+    pub(crate) stn: RefCell<Stn>,
     flaws: RefCell<Vec<Box<dyn Flaw>>>,
+    flaw_depths: RefCell<Vec<usize>>,
+    atoms: RefCell<Vec<AtomId>>,
+    contingents: RefCell<Vec<AtomId>>,
+    int_vars: RefCell<Vec<(linarith::VarId, VarId)>>,
+    branched: RefCell<Vec<(linarith::VarId, Rational)>>,
+    lin_reifs: RefCell<Vec<(linarith::GuardId, Lit)>>,
+    ac_reifs: RefCell<Vec<(ac3rm::ConstraintId, Lit)>>,
+    ac_links: RefCell<Vec<(AcReif, Lit)>>,
+    domain_links: RefCell<Vec<(ac3rm::VarId, i32, VarId)>>,
+    elements: RefCell<Vec<(ac3rm::VarId, String, Slot)>>,
+    element_links: RefCell<Vec<(Lin, Vec<(Lit, Lin)>)>>,
+    strings: RefCell<Vec<String>>,
+    pending_strings: RefCell<Vec<Rc<StringVar>>>,
+    pending_capacities: RefCell<Vec<AtomId>>,
+    string_constraints: RefCell<Vec<(Rc<StringVar>, Rc<StringVar>, bool, Option<VarId>)>>,
+    string_reifs: RefCell<Vec<(Rc<StringVar>, Rc<StringVar>, Lit)>>,
+    products: RefCell<Vec<(Product, VarId)>>,
+    /// The error raised by an unsupported reification, which `assert` can only report as a failure.
+    reify_error: RefCell<Option<SolverError>>,
+    bisected: RefCell<Vec<(usize, Bounds)>>,
     atom_flaws: RefCell<Vec<FlawId>>,
     atom_sigmas: RefCell<Vec<VarId>>,
+    unifications: RefCell<Vec<(VarId, AtomId, AtomId)>>,
     resolvers: RefCell<Vec<Box<dyn Resolver>>>,
     c_flaw: RefCell<Option<FlawId>>,
     c_res: RefCell<Option<ResolverId>>,
+    trail: RefCell<Vec<ResolverId>>,
     active_flaws: Rc<RefCell<HashSet<FlawId>>>,
+    /// The flaws whose activity might have changed at each decision level, so that only those are restored on backtrack.
+    activity_trail: RefCell<Vec<FlawId>>,
+    activity_layers: RefCell<Vec<usize>>,
     flaw_q: RefCell<VecDeque<FlawId>>,
+    flaw_selector: RefCell<Box<dyn FlawSelector>>,
+    resolver_selector: RefCell<Box<dyn ResolverSelector>>,
+    cost_heuristic: RefCell<CostHeuristic>,
+    product_tolerance: RefCell<Rational>,
+    statistics: RefCell<Statistics>,
+    solved: Cell<bool>,
+    limits: RefCell<SolveLimits>,
+    /// The numbers of flaws and resolvers when the current solve started, against which its budget is enforced.
+    budget_start: Cell<(usize, usize)>,
+    /// The global variables declared by the prelude, which are not part of the solutions.
+    prelude_fields: RefCell<HashSet<String>>,
     to_recompute: Rc<RefCell<HashSet<FlawId>>>,
+    timelines: Vec<Box<dyn Timeline>>,
     tx_event: broadcast::Sender<SolverEvent>,
 }
 
 impl SolverState {
     pub(super) fn new(tx_event: broadcast::Sender<SolverEvent>) -> Rc<Self> {
-        Rc::new_cyclic(|core| SolverState {
+        let slv = Rc::new_cyclic(|core| SolverState {
             core: {
                 let core: Weak<SolverState> = core.clone();
                 CommonCore::new(core)
@@ -51,37 +111,196 @@ impl SolverState {
             slv: core.clone(),
             sat: RefCell::new(watchsat::Engine::new()),
             prop_q: RefCell::new(VecDeque::new()),
+            learnts: RefCell::new(Vec::new()),
+            conflicts: RefCell::new(Vec::new()),
             ac: RefCell::new(ac3rm::Engine::new()),
             lin: RefCell::new(linarith::Engine::new()),
+            stn: RefCell::new(Stn::default()),
             flaws: RefCell::new(Vec::new()),
+            flaw_depths: RefCell::new(Vec::new()),
+            atoms: RefCell::new(Vec::new()),
+            contingents: RefCell::new(Vec::new()),
+            int_vars: RefCell::new(Vec::new()),
+            branched: RefCell::new(Vec::new()),
+            lin_reifs: RefCell::new(Vec::new()),
+            ac_reifs: RefCell::new(Vec::new()),
+            ac_links: RefCell::new(Vec::new()),
+            domain_links: RefCell::new(Vec::new()),
+            elements: RefCell::new(Vec::new()),
+            element_links: RefCell::new(Vec::new()),
+            strings: RefCell::new(Vec::new()),
+            pending_strings: RefCell::new(Vec::new()),
+            pending_capacities: RefCell::new(Vec::new()),
+            string_constraints: RefCell::new(Vec::new()),
+            string_reifs: RefCell::new(Vec::new()),
+            products: RefCell::new(Vec::new()),
+            reify_error: RefCell::new(None),
+            bisected: RefCell::new(Vec::new()),
             atom_flaws: RefCell::new(Vec::new()),
             atom_sigmas: RefCell::new(Vec::new()),
+            unifications: RefCell::new(Vec::new()),
             resolvers: RefCell::new(Vec::new()),
             c_flaw: RefCell::new(None),
             c_res: RefCell::new(None),
+            trail: RefCell::new(Vec::new()),
             active_flaws: Rc::new(RefCell::new(HashSet::new())),
+            activity_trail: RefCell::new(Vec::new()),
+            activity_layers: RefCell::new(Vec::new()),
             flaw_q: RefCell::new(VecDeque::new()),
+            flaw_selector: RefCell::new(Box::new(MostExpensiveFlaw)),
+            resolver_selector: RefCell::new(Box::new(CheapestResolver)),
+            cost_heuristic: RefCell::new(CostHeuristic::default()),
+            product_tolerance: RefCell::new(Rational::new(1, 1000)),
+            statistics: RefCell::new(Statistics::default()),
+            solved: Cell::new(false),
+            limits: RefCell::new(SolveLimits::default()),
+            budget_start: Cell::new((0, 0)),
+            prelude_fields: RefCell::new(HashSet::new()),
             to_recompute: Rc::new(RefCell::new(HashSet::new())),
+            timelines: vec![Box::new(StateVariable::new(core.clone())), Box::new(ReusableResource::new(core.clone())), Box::new(ConsumableResource::new(core.clone())), Box::new(PropositionalState::new(core.clone()))],
             tx_event,
-        })
+        });
+        slv.read(include_str!("prelude.rddl")).expect("Failed to read the prelude");
+        slv.prelude_fields.replace(slv.get_fields().iter().map(|field| field.name().to_string()).collect());
+        slv
     }
 
     pub(super) fn read(&self, script: &str) -> Result<(), SolverError> {
         trace!("Reading RiDDle script");
-        self.core.read(script).map_err(|e| SolverError::RuntimeError(format!("Failed to read RiDDle script: {:?}", e)))
+        self.renew_statistics();
+        let read = self.core.read(script);
+        if let Some(err) = self.reify_error.take() {
+            return Err(err);
+        }
+        read.map_err(|e| SolverError::RuntimeError(format!("Failed to read RiDDle script: {:?}", e)))?;
+        // The consequences of the model, e.g., the instances pruned by the element constraints, are visible before solving, while its conflicts are reported by the next solve
+        if self.propagate().is_err() {
+            self.learn(vec![FALSE_LIT]);
+        }
+        Ok(())
+    }
+
+    pub(super) fn set_flaw_selector(&self, selector: Box<dyn FlawSelector>) {
+        self.flaw_selector.replace(selector);
+    }
+
+    pub(super) fn set_resolver_selector(&self, selector: Box<dyn ResolverSelector>) {
+        self.resolver_selector.replace(selector);
+    }
+
+    pub(super) fn set_cost_heuristic(&self, heuristic: CostHeuristic) {
+        self.cost_heuristic.replace(heuristic);
+        self.to_recompute.borrow_mut().extend(self.flaws.borrow().iter().map(|flaw| flaw.id()));
+        self.update_costs();
+    }
+
+    pub(super) fn set_product_tolerance(&self, tolerance: Rational) {
+        self.product_tolerance.replace(tolerance);
+    }
+
+    pub(super) fn statistics(&self) -> Statistics {
+        self.statistics.borrow().clone()
     }
 
     pub fn enqueue(&self, lit: Lit) {
         self.prop_q.borrow_mut().push_back(lit);
     }
 
-    pub(super) fn solve(&self) -> Result<(), SolverError> {
+    /// The clause is added to the SAT engine once the assignment no longer violates it.
+    pub(crate) fn learn(&self, clause: Vec<Lit>) {
+        trace!("Learning clause: {}", clause.iter().map(|lit| lit.to_string()).collect::<Vec<_>>().join(" ∨ "));
+        self.learnts.borrow_mut().push(clause);
+    }
+
+    pub(crate) fn explain_ac(&self, lit: Lit, constraints: &[ac3rm::ConstraintId]) -> Vec<Lit> {
+        let resolvers = self.resolvers.borrow();
+        let sat = self.sat.borrow();
+        let mut clause = vec![!lit];
+        for res in resolvers.iter() {
+            if pos(res.rho()) != lit && sat.value(res.rho()) == LBool::True && res.ac_constraints().is_some_and(|constrs| constrs.iter().any(|c| constraints.contains(c))) {
+                clause.push(neg(res.rho()));
+            }
+        }
+        for (constraint, reif) in self.ac_reifs.borrow().iter() {
+            if *reif != lit && sat.lit_value(reif) == LBool::True && constraints.contains(constraint) && !clause.contains(&!*reif) {
+                clause.push(!*reif);
+            }
+        }
+        clause
+    }
+
+    pub(crate) fn explain_lin(&self, lit: Lit, guards: &[linarith::GuardId]) -> Vec<Lit> {
+        let mut clause = vec![!lit];
+        for (guard, reason) in self.active_guards() {
+            if reason != lit && guards.contains(&guard) && !clause.contains(&!reason) {
+                clause.push(!reason);
+            }
+        }
+        clause
+    }
+
+    fn active_guards(&self) -> Vec<(linarith::GuardId, Lit)> {
+        let sat = self.sat.borrow();
+        let mut guards: Vec<_> = self.resolvers.borrow().iter().filter(|res| sat.value(res.rho()) == LBool::True).filter_map(|res| res.lin_guard().map(|guard| (guard, pos(res.rho())))).collect();
+        guards.extend(self.lin_reifs.borrow().iter().filter(|(_, lit)| sat.lit_value(lit) == LBool::True).copied());
+        guards
+    }
+
+    fn explain_conflicts(&self) {
+        let conflicts = std::mem::take(&mut *self.conflicts.borrow_mut());
+        for (lit, conflict) in conflicts {
+            let clause = match conflict {
+                Conflict::Ac(constraints) => self.explain_ac(lit, &constraints),
+                Conflict::Lin(guards) => self.explain_lin(lit, &guards),
+            };
+            self.learn(clause);
+            self.enqueue(!lit);
+        }
+    }
+
+    fn add_learnts(&self) -> bool {
+        let learnts = std::mem::take(&mut *self.learnts.borrow_mut());
+        let mut consistent = true;
+        for clause in learnts {
+            if self.sat.borrow_mut().add_clause(clause.clone()).is_err() {
+                consistent = false;
+                self.learnts.borrow_mut().push(clause);
+            } else {
+                self.statistics.borrow_mut().learnts += 1;
+            }
+        }
+        consistent
+    }
+
+    pub(super) fn solve(&self, limits: SolveLimits) -> Result<(), SolverError> {
         info!("Solving problem...");
-        self.build_graph()?;
+        self.renew_statistics();
+        self.solved.set(true);
+        self.limits.replace(limits);
+        self.budget_start.set((self.flaws.borrow().len(), self.resolvers.borrow().len()));
+        let start = Instant::now();
+        self.create_string_domains();
+        self.create_capacity_links();
+        let graph = self.build_graph();
+        self.statistics.borrow_mut().graph_time += start.elapsed();
+        graph?;
+
+        let start = Instant::now();
+        let search = self.search();
+        self.statistics.borrow_mut().search_time += start.elapsed();
+        search
+    }
+
+    fn renew_statistics(&self) {
+        if self.solved.replace(false) {
+            self.statistics.replace(Statistics::default());
+        }
+    }
 
+    fn search(&self) -> Result<(), SolverError> {
         loop {
-            let resolvers = self.resolvers.borrow();
-            if let Some(flaw) = self.get_most_expensive_flaw() {
+            self.check_limits()?;
+            if let Some(flaw) = self.select_flaw() {
                 trace!("Best flaw to resolve: {}", flaw);
                 let (is_expanded, cost) = {
                     let flaws = self.flaws.borrow();
@@ -89,33 +308,269 @@ impl SolverState {
                     (f.is_expanded(), f.cost())
                 };
                 assert!(is_expanded, "Most expensive flaw is not expanded, problem is inconsistent");
-                assert!(!cost.is_infinite(), "Most expensive flaw has infinite cost, problem is inconsistent");
                 self.set_current_flaw(Some(flaw));
-                if let Some(resolver) = self.get_least_expensive_resolver(flaw) {
+                if cost.is_infinite() {
+                    trace!("Flaw {} has infinite cost, backtracking", flaw);
+                    self.backtrack()?;
+                } else if let Some(resolver) = self.select_resolver(flaw) {
                     trace!("Best resolver to apply: {}", resolver);
                     self.set_current_resolver(Some(resolver));
-                    if let Err(_) = self.sat.borrow_mut().assert(pos(resolvers.get(*resolver).expect("Invalid resolver ID").rho())) {
-                        warn!("Failed to assert resolver {}, problem is inconsistent", resolver);
-                        return Err(SolverError::Inconsistent);
-                    }
-                    self.propagate()?;
+                    self.take_decision(resolver)?;
                     self.set_current_resolver(None);
                 } else {
-                    warn!("No applicable resolver for flaw {}, problem is inconsistent", flaw);
-                    return Err(SolverError::Inconsistent);
+                    trace!("No applicable resolver for flaw {}, backtracking", flaw);
+                    self.backtrack()?;
                 }
                 self.set_current_flaw(None);
                 self.update_costs();
             } else {
-                info!("Hurray! No more flaws to resolve. Problem is consistent.");
+                match self.detect_flaws() {
+                    Ok(true) => self.update_costs(),
+                    Ok(false) if !self.timelines.iter().all(|timeline| timeline.is_consistent()) => {
+                        trace!("The current values violate some timeline, backtracking");
+                        self.backtrack()?;
+                        self.update_costs();
+                    }
+                    Ok(false) if self.check_controllability() => {
+                        info!("Hurray! No more flaws to resolve. Problem is consistent.");
+                        return Ok(());
+                    }
+                    Ok(false) => {
+                        trace!("The plan is not dynamically controllable, backjumping");
+                        self.backjump()?;
+                        self.update_costs();
+                    }
+                    Err(SolverError::Inconsistent) => {
+                        trace!("New flaws lead to a conflict, backtracking");
+                        self.backtrack()?;
+                        self.update_costs();
+                    }
+                    Err(e) => return Err(e),
+                }
+            };
+        }
+    }
+
+    fn check_limits(&self) -> Result<(), SolverError> {
+        let limits = self.limits.borrow();
+        if limits.cancellation.as_ref().is_some_and(|handle| handle.is_cancelled()) {
+            warn!("Solving has been cancelled");
+            return Err(SolverError::Cancelled);
+        }
+        if limits.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            warn!("Solving deadline has passed");
+            return Err(SolverError::Timeout);
+        }
+        let (flaws, resolvers) = self.budget_start.get();
+        if limits.max_flaws.is_some_and(|max| self.flaws.borrow().len() - flaws > max) || limits.max_resolvers.is_some_and(|max| self.resolvers.borrow().len() - resolvers > max) {
+            warn!("Solving budget has been exhausted");
+            return Err(SolverError::Timeout);
+        }
+        Ok(())
+    }
+
+    fn take_decision(&self, resolver: ResolverId) -> Result<(), SolverError> {
+        let rho = self.resolvers.borrow().get(*resolver).expect("Invalid resolver ID").rho();
+        trace!("Taking decision {} ({}) at level {}", resolver, rho, self.trail.borrow().len() + 1);
+        self.statistics.borrow_mut().decisions += 1;
+        self.sat.borrow_mut().push();
+        self.ac.borrow_mut().push();
+        self.lin.borrow_mut().push();
+        self.stn.borrow_mut().push();
+        for timeline in self.timelines.iter() {
+            timeline.push();
+        }
+        self.activity_layers.borrow_mut().push(self.activity_trail.borrow().len());
+        self.trail.borrow_mut().push(resolver);
+        let asserted = self.sat.borrow_mut().assert(pos(rho)).is_ok();
+        if !asserted || self.propagate().is_err() {
+            trace!("Resolver {} leads to a conflict", resolver);
+            self.backtrack()?;
+        }
+        Ok(())
+    }
+
+    fn backtrack(&self) -> Result<(), SolverError> {
+        self.statistics.borrow_mut().conflicts += 1;
+        loop {
+            let Some(resolver) = self.trail.borrow_mut().pop() else {
+                warn!("No more decisions to undo, problem is inconsistent");
+                return Err(SolverError::Inconsistent);
+            };
+            trace!("Backtracking resolver {}", resolver);
+            self.explain_conflicts();
+            self.prop_q.borrow_mut().clear();
+            self.sat.borrow_mut().pop();
+            self.ac.borrow_mut().pop();
+            self.lin.borrow_mut().pop();
+            self.stn.borrow_mut().pop();
+            for timeline in self.timelines.iter() {
+                timeline.pop();
+            }
+            self.restore_active_flaws();
+            if !self.add_learnts() {
+                trace!("Learnt clauses are still violated, backtracking further");
+                continue;
+            }
+
+            let rho = self.resolvers.borrow().get(*resolver).expect("Invalid resolver ID").rho();
+            let rho_val = self.sat.borrow().value(rho);
+            let excluded = match rho_val {
+                LBool::False => true,
+                LBool::True => false,
+                LBool::Undef => self.sat.borrow_mut().assert(neg(rho)).is_ok(),
+            };
+            if excluded && self.propagate().is_ok() {
                 return Ok(());
+            }
+        }
+    }
+
+    fn backjump(&self) -> Result<(), SolverError> {
+        self.statistics.borrow_mut().conflicts += 1;
+        while !self.add_learnts() {
+            let Some(resolver) = self.trail.borrow_mut().pop() else {
+                warn!("No more decisions to undo, problem is inconsistent");
+                return Err(SolverError::Inconsistent);
             };
+            trace!("Backjumping over resolver {}", resolver);
+            self.explain_conflicts();
+            self.prop_q.borrow_mut().clear();
+            self.sat.borrow_mut().pop();
+            self.ac.borrow_mut().pop();
+            self.lin.borrow_mut().pop();
+            self.stn.borrow_mut().pop();
+            for timeline in self.timelines.iter() {
+                timeline.pop();
+            }
+            self.restore_active_flaws();
         }
+        if self.propagate().is_err() {
+            self.backtrack()?;
+        }
+        Ok(())
+    }
+
+    fn check_integrality(&self) -> Vec<FlawId> {
+        let mut flaws = Vec::new();
+        for (var, phi) in self.int_vars.borrow().iter() {
+            if self.sat.borrow().value(*phi) != LBool::True {
+                continue;
+            }
+            let val = self.arith_val(&Lin::from(*var));
+            if val.floor() == val || self.branched.borrow().contains(&(*var, val.floor())) {
+                continue;
+            }
+            self.branched.borrow_mut().push((*var, val.floor()));
+            let flaw_id = FlawId(self.flaws.borrow().len());
+            self.add_flaw(IntegralityFlaw::new(self.slv.clone(), flaw_id, *phi, *var, val));
+            flaws.push(flaw_id);
+        }
+        flaws
+    }
+
+    fn check_products(&self) -> Result<Vec<FlawId>, SolverError> {
+        let tolerance = *self.product_tolerance.borrow();
+        let mut flaws = Vec::new();
+        for (id, (product, phi)) in self.products.borrow().iter().enumerate() {
+            if self.sat.borrow().value(*phi) != LBool::True {
+                continue;
+            }
+            let error = self.arith_val(&product.z) - self.arith_val(&product.x) * self.arith_val(&product.y);
+            if -tolerance <= error && error <= tolerance {
+                continue;
+            }
+            // The McCormick envelope is built on the bounds of the factors at this point, regardless of the order in which they were constrained
+            let bounds = [self.arith_lb(&product.x), self.arith_ub(&product.x), self.arith_lb(&product.y), self.arith_ub(&product.y)];
+            if bounds.iter().any(|bound| *bound == Rational::NEGATIVE_INFINITY || *bound == Rational::POSITIVE_INFINITY) {
+                return Err(SolverError::RuntimeError("The factors of a non-linear product must be bounded".into()));
+            }
+            if self.bisected.borrow().contains(&(id, bounds)) {
+                continue;
+            }
+            self.bisected.borrow_mut().push((id, bounds));
+            let flaw_id = FlawId(self.flaws.borrow().len());
+            self.add_flaw(ProductFlaw::new(self.slv.clone(), flaw_id, *phi, product.clone(), bounds));
+            flaws.push(flaw_id);
+        }
+        Ok(flaws)
+    }
+
+    fn check_controllability(&self) -> bool {
+        let mut links = Vec::new();
+        let mut link_atoms = Vec::new();
+        for atom_id in self.contingents.borrow().iter() {
+            let phi = self.flaws.borrow().get(*self.get_atom_flaw(*atom_id)).expect("Invalid flaw ID").phi();
+            if self.sat.borrow().value(phi) != LBool::True || self.sat.borrow().value(self.get_sigma(*atom_id)) != LBool::True {
+                continue;
+            }
+            let atom = self.get_atom(*atom_id).expect("Atom should exist");
+            let (start, end) = (numeric_lin(&atom.get("start").expect("Interval should have a start")), numeric_lin(&atom.get("end").expect("Interval should have an end")));
+            let (Some((activation, start_offset)), Some((Some(contingent), end_offset))) = (as_node(&start), as_node(&end)) else {
+                warn!("Contingent atom {} does not end in a variable, ignoring its uncertainty", atom_id);
+                continue;
+            };
+            let offset = start_offset - end_offset;
+            // The environment might choose any duration the bounds still allow, not only those of the current assignment
+            let min = self.arith_lb(&numeric_lin(&atom.get("min_duration").expect("Contingent interval should have a minimum duration")));
+            let max = self.arith_ub(&numeric_lin(&atom.get("max_duration").expect("Contingent interval should have a maximum duration")));
+            links.push(ContingentLink { activation, contingent, lb: min + offset, ub: max + offset });
+            link_atoms.push(*atom_id);
+        }
+        if links.is_empty() {
+            return true;
+        }
+
+        let Err((guards, involved)) = self.stn.borrow().check_controllability(&links) else {
+            return true;
+        };
+        // The plan is excluded by either discarding one of the involved resolvers or one of the involved contingent atoms
+        let mut clause: Vec<Lit> = Vec::new();
+        for (guard, lit) in self.active_guards() {
+            if guards.contains(&guard) && !clause.contains(&!lit) {
+                clause.push(!lit);
+            }
+        }
+        for link in involved {
+            let atom_id = link_atoms[link];
+            clause.push(neg(self.flaws.borrow().get(*self.get_atom_flaw(atom_id)).expect("Invalid flaw ID").phi()));
+            clause.push(neg(self.get_sigma(atom_id)));
+        }
+        self.learn(clause);
+        false
+    }
+
+    /// The listeners only track forward assignments, hence the active flaws are recomputed after backtracking.
+    /// Records that the activity of the given flaw might change at the current decision level.
+    fn trail_activity(&self, flaw_id: FlawId) {
+        if !self.activity_layers.borrow().is_empty() {
+            self.activity_trail.borrow_mut().push(flaw_id);
+        }
+    }
+
+    fn restore_active_flaws(&self) {
+        let len = self.activity_layers.borrow_mut().pop().expect("No decision level to restore");
+        let touched = self.activity_trail.borrow_mut().split_off(len);
+        let flaws = self.flaws.borrow();
+        let resolvers = self.resolvers.borrow();
+        let sat = self.sat.borrow();
+        let mut active_flaws = self.active_flaws.borrow_mut();
+        for flaw_id in touched.iter() {
+            let flaw = flaws.get(**flaw_id).expect("Invalid flaw ID");
+            if sat.value(flaw.phi()) == LBool::True && !flaw.resolvers().iter().any(|res_id| sat.value(resolvers.get(**res_id).expect("Invalid resolver ID").rho()) == LBool::True) {
+                active_flaws.insert(*flaw_id);
+            } else {
+                active_flaws.remove(flaw_id);
+            }
+        }
+        trace!("Active flaws count: {}", active_flaws.len());
+        self.to_recompute.borrow_mut().extend(touched);
     }
 
     pub fn add_flaw(&self, flaw: Box<dyn Flaw>) {
         let flaw_id = flaw.id();
         trace!("Adding flaw: {} ({})", flaw_id, flaw.phi());
+        *self.statistics.borrow_mut().flaws.entry(flaw.kind()).or_default() += 1;
         let _ = self.tx_event.send(SolverEvent::NewFlaw {
             flaw_id,
             phi: flaw.phi(),
@@ -130,9 +585,11 @@ impl SolverState {
             active_flaws.insert(flaw_id);
             trace!("Active flaws count: {}", active_flaws.len());
         }
+        self.trail_activity(flaw_id);
         self.sat.borrow_mut().add_listener(flaw.phi(), {
             let tx_event = self.tx_event.clone();
             let active_flaws = self.active_flaws.clone();
+            let solver = self.slv.upgrade().expect("SolverState has been dropped");
             move |_var, val| {
                 if val == LBool::True {
                     trace!("Flaw {} became active", flaw_id);
@@ -140,10 +597,13 @@ impl SolverState {
                     assert!(!active_flaws.contains(&flaw_id), "Flaw {} is already active", flaw_id);
                     active_flaws.insert(flaw_id);
                     trace!("Active flaws count: {}", active_flaws.len());
+                    solver.trail_activity(flaw_id);
                 }
                 let _ = tx_event.send(SolverEvent::FlawStatusUpdate { flaw_id, status: val });
             }
         });
+        let depth = if flaw.causes().is_empty() { 0 } else { self.c_flaw.borrow().map_or(0, |c_flaw| self.flaw_depths.borrow()[*c_flaw] + 1) };
+        self.flaw_depths.borrow_mut().push(depth);
         self.flaw_q.borrow_mut().push_back(flaw_id);
         self.flaws.borrow_mut().push(flaw);
     }
@@ -162,11 +622,644 @@ impl SolverState {
         *self.atom_sigmas.borrow().get(*atom_id).expect("Atom should have a corresponding flaw")
     }
 
+    pub(crate) fn active_atoms_on(&self, object: ObjectId) -> Vec<Rc<Atom>> {
+        let mut atoms = Vec::new();
+        for atom_id in self.atoms.borrow().iter() {
+            let phi = self.flaws.borrow().get(*self.get_atom_flaw(*atom_id)).expect("Invalid flaw ID").phi();
+            if self.sat.borrow().value(phi) != LBool::True || self.sat.borrow().value(self.get_sigma(*atom_id)) != LBool::True {
+                continue;
+            }
+            let atom = self.get_atom(*atom_id).expect("Atom should exist");
+            let on_object = match atom.get(TAU) {
+                Some(Slot::ObjectRef(tau)) => *tau == *object,
+                Some(Slot::Primitive(var)) => var.as_any().downcast_ref::<EnumVar>().is_some_and(|var| self.ac.borrow().val(var.var).contains(&(*object as i32))),
+                _ => false,
+            };
+            if on_object {
+                atoms.push(atom);
+            }
+        }
+        atoms
+    }
+
+    fn assert_temporal_constraints(&self, atom: &Atom) -> bool {
+        let bool_type = Rc::downgrade(&self.bool_type());
+        let origin = self.get("origin").expect("Prelude should declare the origin");
+        let horizon = self.get("horizon").expect("Prelude should declare the horizon");
+        let mut terms: Vec<Rc<BoolExpr>> = Vec::new();
+        if is_subpredicate_of(&atom.predicate(), "Interval") {
+            let (start, end, duration) = (atom.get("start").expect("Interval should have a start"), atom.get("end").expect("Interval should have an end"), atom.get("duration").expect("Interval should have a duration"));
+            terms.push(Rc::new(BoolExpr::Leq { var_type: bool_type.clone(), left: self.new_real(0, 1), right: duration.clone() }));
+            let start_plus_duration = self.sum(&[start.clone(), duration]).expect("Interval bounds should be numeric");
+            terms.push(Rc::new(BoolExpr::Eq { var_type: bool_type.clone(), left: start_plus_duration, right: end.clone() }));
+            terms.push(Rc::new(BoolExpr::Leq { var_type: bool_type.clone(), left: origin, right: start }));
+            terms.push(Rc::new(BoolExpr::Leq { var_type: bool_type.clone(), left: end.clone(), right: horizon }));
+            if is_subpredicate_of(&atom.predicate(), "ContingentInterval") {
+                // The duration is chosen by the environment, hence it is bounded as a difference between the end and the start
+                let (min, max) = (atom.get("min_duration").expect("Contingent interval should have a minimum duration"), atom.get("max_duration").expect("Contingent interval should have a maximum duration"));
+                terms.push(Rc::new(BoolExpr::Leq { var_type: bool_type.clone(), left: self.new_real(0, 1), right: min.clone() }));
+                let earliest_end = self.sum(&[start.clone(), min]).expect("Contingent interval bounds should be numeric");
+                terms.push(Rc::new(BoolExpr::Leq { var_type: bool_type.clone(), left: earliest_end, right: end.clone() }));
+                let latest_end = self.sum(&[start, max]).expect("Contingent interval bounds should be numeric");
+                terms.push(Rc::new(BoolExpr::Leq { var_type: bool_type.clone(), left: end, right: latest_end }));
+                self.contingents.borrow_mut().push(atom.id());
+            }
+        } else if is_subpredicate_of(&atom.predicate(), "Impulse") {
+            let at = atom.get("at").expect("Impulse should have an `at`");
+            terms.push(Rc::new(BoolExpr::Leq { var_type: bool_type.clone(), left: origin, right: at.clone() }));
+            terms.push(Rc::new(BoolExpr::Leq { var_type: bool_type.clone(), left: at, right: horizon }));
+        } else {
+            return true;
+        }
+        self.assert(Rc::new(BoolExpr::And { var_type: bool_type, terms }))
+    }
+
+    fn assert_capacity_constraints(&self, atom: &Atom) -> bool {
+        if !is_subpredicate_of(&atom.predicate(), "Use") {
+            return true;
+        }
+        let capacity = match atom.get(TAU) {
+            Some(Slot::ObjectRef(tau)) => self.get_object(tau).and_then(|obj| obj.get("capacity")),
+            Some(Slot::Primitive(_)) => {
+                // The element variable of the capacity propagates, hence it is created once the atom is complete
+                self.pending_capacities.borrow_mut().push(atom.id());
+                return true;
+            }
+            _ => None,
+        };
+        let (Some(amount), Some(capacity)) = (atom.get("amount"), capacity) else {
+            return true;
+        };
+        self.assert(Rc::new(BoolExpr::Leq { var_type: Rc::downgrade(&self.bool_type()), left: amount, right: capacity }))
+    }
+
+    /// Constrains the amount of the uses whose resource is an object variable not to exceed the capacity of the chosen resource.
+    fn create_capacity_links(&self) {
+        let pending = std::mem::take(&mut *self.pending_capacities.borrow_mut());
+        for atom_id in pending {
+            let atom = self.get_atom(atom_id).expect("Atom should exist");
+            let capacity = match atom.get(TAU) {
+                Some(Slot::Primitive(var)) => var.as_any().downcast_ref::<EnumVar>().and_then(|var| self.element(var, "capacity").ok()),
+                _ => None,
+            };
+            let (Some(amount), Some(capacity)) = (atom.get("amount"), capacity) else {
+                continue;
+            };
+            if !self.assert(Rc::new(BoolExpr::Leq { var_type: Rc::downgrade(&self.bool_type()), left: amount, right: capacity })) {
+                warn!("Atom {} exceeds the capacity of its resource", atom_id);
+                let (rho, _) = self.current_cause();
+                self.learn(vec![!rho]);
+            }
+        }
+    }
+
+    /// Constraints on released variables go to both engines, so that the STN keeps track of all the temporal constraints.
+    fn new_le_constraint(&self, left: &Lin, right: &Lin, strict: bool, guard: Option<linarith::GuardId>) -> bool {
+        let mut diff = left.clone();
+        diff += &(-right.clone());
+        if let Some((to, from, known_term)) = as_difference(&diff) {
+            // `to - from + known_term <= 0`
+            if self.stn.borrow_mut().new_constraint(to, from, Dist::new(-known_term, strict), guard).is_err() {
+                return false;
+            }
+            if !self.has_released(&diff) {
+                return true;
+            }
+        }
+        if !self.release_to_lin(&diff) {
+            return false;
+        }
+        if strict { self.lin.borrow_mut().new_lt(left, right, true, guard).is_ok() } else { self.lin.borrow_mut().new_le(left, right, guard).is_ok() }
+    }
+
+    fn new_eq_constraint(&self, left: &Lin, right: &Lin, guard: Option<linarith::GuardId>) -> bool {
+        let mut diff = left.clone();
+        diff += &(-right.clone());
+        if let Some((to, from, known_term)) = as_difference(&diff) {
+            let mut stn = self.stn.borrow_mut();
+            if stn.new_constraint(to, from, Dist::new(-known_term, false), guard).is_err() || stn.new_constraint(from, to, Dist::new(known_term, false), guard).is_err() {
+                return false;
+            }
+            drop(stn);
+            if !self.has_released(&diff) {
+                return true;
+            }
+        }
+        if !self.release_to_lin(&diff) {
+            return false;
+        }
+        self.lin.borrow_mut().new_eq(left, right, guard).is_ok()
+    }
+
+    /// The product is relaxed by `check_products`, once the factors have their bounds.
+    fn new_product(&self, x: Lin, y: Lin, int: bool) -> Lin {
+        let var = self.lin.borrow_mut().add_var();
+        let (rho, _) = self.current_cause();
+        if int {
+            self.int_vars.borrow_mut().push((var, rho.var()));
+        }
+        let product = Product { x, y, z: Lin::from(var) };
+        let z = product.z.clone();
+        self.products.borrow_mut().push((product, rho.var()));
+        z
+    }
+
+    fn intern(&self, value: &str) -> i32 {
+        let mut strings = self.strings.borrow_mut();
+        match strings.iter().position(|string| string == value) {
+            Some(pos) => pos as i32,
+            None => {
+                strings.push(value.to_string());
+                (strings.len() - 1) as i32
+            }
+        }
+    }
+
+    fn new_string_constraint(&self, left: Rc<StringVar>, right: Rc<StringVar>, equal: bool, rho: Option<VarId>) -> bool {
+        let (Some(left_var), Some(right_var)) = (left.var.get(), right.var.get()) else {
+            self.string_constraints.borrow_mut().push((left, right, equal, rho));
+            return true;
+        };
+        let constraint = if equal { ac3rm::Constraint::Equality(*left_var, *right_var) } else { ac3rm::Constraint::Inequality(*left_var, *right_var) };
+        let constraint_id = self.ac.borrow_mut().new_constraint(constraint);
+        if let Some(rho) = rho
+            && self.sat.borrow().value(rho) == LBool::Undef
+        {
+            self.resolvers.borrow_mut().get_mut(*self.c_res.borrow().unwrap()).expect("Invalid resolver ID").add_ac_constraint(constraint_id);
+            true
+        } else {
+            self.ac.borrow_mut().assert(constraint_id).is_ok()
+        }
+    }
+
+    /// The domains only range over the literals read so far.
+    fn create_string_domains(&self) {
+        let pending = std::mem::take(&mut *self.pending_strings.borrow_mut());
+        if pending.is_empty() {
+            return;
+        }
+        let vals: Vec<i32> = (0..self.strings.borrow().len() as i32).collect();
+        for var in pending {
+            var.var.set(self.ac.borrow_mut().add_var(vals.clone())).expect("String variable should be pending");
+        }
+        for (left, right, lit) in std::mem::take(&mut *self.string_reifs.borrow_mut()) {
+            let eq = self.reify_ac(AcReif::Equality(left.var(), right.var()));
+            self.learn(vec![!lit, eq]);
+            self.learn(vec![lit, !eq]);
+        }
+        for (left, right, equal, rho) in std::mem::take(&mut *self.string_constraints.borrow_mut()) {
+            let constraint = if equal { ac3rm::Constraint::Equality(left.var(), right.var()) } else { ac3rm::Constraint::Inequality(left.var(), right.var()) };
+            let constraint_id = self.ac.borrow_mut().new_constraint(constraint);
+            match rho {
+                // the resolver might have been applied already, hence the constraint is linked to its literal rather than added to it
+                Some(rho) if self.sat.borrow().value(rho) == LBool::Undef => self.link_ac_constraint(pos(rho), constraint_id),
+                Some(rho) if self.sat.borrow().value(rho) == LBool::False => {}
+                _ => {
+                    if self.ac.borrow_mut().assert(constraint_id).is_err() {
+                        self.learn(vec![!rho.map_or(TRUE_LIT, pos)]);
+                    }
+                }
+            }
+        }
+    }
+
+    pub(crate) fn reify(&self, expr: &BoolExpr) -> Result<Lit, SolverError> {
+        match expr {
+            BoolExpr::Term { term, .. } => Ok(bool_lit(term)),
+            BoolExpr::Not { term, .. } => Ok(!self.reify(term)?),
+            BoolExpr::And { terms, .. } => {
+                let lits = terms.iter().map(|term| self.reify(term)).collect::<Result<Vec<Lit>, _>>()?;
+                Ok(!self.reify_or(lits.into_iter().map(|lit| !lit).collect()))
+            }
+            BoolExpr::Or { terms, .. } => {
+                let lits = terms.iter().map(|term| self.reify(term)).collect::<Result<Vec<Lit>, _>>()?;
+                Ok(self.reify_or(lits))
+            }
+            BoolExpr::Lt { left, right, .. } => Ok(self.reify_le(&numeric_lin(left), &numeric_lin(right), true)),
+            BoolExpr::Leq { left, right, .. } => Ok(self.reify_le(&numeric_lin(left), &numeric_lin(right), false)),
+            BoolExpr::Eq { left, right, .. } => match (left, right) {
+                (Slot::Primitive(left), Slot::Primitive(right)) => {
+                    if let (Some(left), Some(right)) = (left.clone().as_any().downcast_ref::<BoolVar>(), right.clone().as_any().downcast_ref::<BoolVar>()) {
+                        // `lit` is true iff `left` and `right` are both true or both false
+                        let both = self.reify_or(vec![!left.lit, !right.lit]);
+                        let neither = self.reify_or(vec![left.lit, right.lit]);
+                        Ok(self.reify_or(vec![!both, !neither]))
+                    } else if let (Some(left), Some(right)) = (left.clone().as_any().downcast_ref::<ArithVar>(), right.clone().as_any().downcast_ref::<ArithVar>()) {
+                        let lt = self.reify_le(&left.lin, &right.lin, true);
+                        let gt = self.reify_le(&right.lin, &left.lin, true);
+                        Ok(!self.reify_or(vec![lt, gt]))
+                    } else if let (Ok(left), Ok(right)) = (left.clone().as_any().downcast::<StringVar>(), right.clone().as_any().downcast::<StringVar>()) {
+                        if let (Some(left_var), Some(right_var)) = (left.var.get(), right.var.get()) {
+                            Ok(self.reify_ac(AcReif::Equality(*left_var, *right_var)))
+                        } else {
+                            let lit = pos(self.sat.borrow_mut().add_var());
+                            self.string_reifs.borrow_mut().push((left, right, lit));
+                            Ok(lit)
+                        }
+                    } else if let (Some(left), Some(right)) = (left.clone().as_any().downcast_ref::<EnumVar>(), right.clone().as_any().downcast_ref::<EnumVar>()) {
+                        Ok(self.reify_ac(AcReif::Equality(left.var, right.var)))
+                    } else {
+                        Err(SolverError::RuntimeError("Cannot reify the equality of variables of different types".into()))
+                    }
+                }
+                (Slot::Primitive(var), Slot::ObjectRef(obj)) | (Slot::ObjectRef(obj), Slot::Primitive(var)) => {
+                    if let Some(var) = var.clone().as_any().downcast_ref::<EnumVar>() {
+                        Ok(self.reify_ac(AcReif::Set(var.var, **obj as i32)))
+                    } else {
+                        Err(SolverError::RuntimeError("Cannot reify the equality of an object with a variable which is not an object variable".into()))
+                    }
+                }
+                (Slot::ObjectRef(left), Slot::ObjectRef(right)) => Ok(if left == right { TRUE_LIT } else { FALSE_LIT }),
+                _ => Err(SolverError::RuntimeError("Cannot reify the equality of the given values".into())),
+            },
+        }
+    }
+
+    fn reify_or(&self, lits: Vec<Lit>) -> Lit {
+        let lit = pos(self.sat.borrow_mut().add_var());
+        let mut sat = self.sat.borrow_mut();
+        for disjunct in lits.iter() {
+            sat.add_clause(vec![lit, !*disjunct]).expect("Failed to add reification clause");
+        }
+        let mut clause = vec![!lit];
+        clause.extend(lits);
+        sat.add_clause(clause).expect("Failed to add reification clause");
+        lit
+    }
+
+    fn reify_le(&self, left: &Lin, right: &Lin, strict: bool) -> Lit {
+        let lit = pos(self.sat.borrow_mut().add_var());
+        let then_guard = self.lin.borrow_mut().add_guard();
+        let else_guard = self.lin.borrow_mut().add_guard();
+        if !self.new_le_constraint(left, right, strict, Some(then_guard)) {
+            self.sat.borrow_mut().add_clause(vec![!lit]).expect("Failed to add reification clause");
+        }
+        if !self.new_le_constraint(right, left, !strict, Some(else_guard)) {
+            self.sat.borrow_mut().add_clause(vec![lit]).expect("Failed to add reification clause");
+        }
+        self.link_lin_guard(lit, then_guard);
+        self.link_lin_guard(!lit, else_guard);
+        lit
+    }
+
+    fn reify_ac(&self, reif: AcReif) -> Lit {
+        let lit = pos(self.sat.borrow_mut().add_var());
+        let (then, otherwise) = reif.constraints();
+        let then = self.ac.borrow_mut().new_constraint(then);
+        let otherwise = self.ac.borrow_mut().new_constraint(otherwise);
+        self.link_ac_constraint(lit, then);
+        self.link_ac_constraint(!lit, otherwise);
+        if let Some(val) = reif.entailment(&self.ac.borrow()) {
+            self.enqueue(if val { lit } else { !lit });
+        }
+        self.ac_links.borrow_mut().push((reif, lit));
+        lit
+    }
+
+    fn propagate_ac_links(&self) -> bool {
+        let ac = self.ac.borrow();
+        let sat = self.sat.borrow();
+        let mut found = false;
+        for (reif, lit) in self.ac_links.borrow().iter() {
+            if sat.lit_value(lit) != LBool::Undef {
+                continue;
+            }
+            if let Some(val) = reif.entailment(&ac) {
+                self.enqueue(if val { *lit } else { !*lit });
+                found = true;
+            }
+        }
+        found
+    }
+
+    /// Falsifies the given variable once the value leaves the domain of the AC variable.
+    pub(crate) fn link_domain_value(&self, var: ac3rm::VarId, val: i32, rho: VarId) {
+        self.domain_links.borrow_mut().push((var, val, rho));
+    }
+
+    fn propagate_domain_links(&self) -> bool {
+        let ac = self.ac.borrow();
+        let sat = self.sat.borrow();
+        let mut found = false;
+        for (var, val, rho) in self.domain_links.borrow().iter() {
+            if sat.value(*rho) != LBool::False && !ac.val(*var).contains(val) {
+                self.enqueue(neg(*rho));
+                found = true;
+            }
+        }
+        found
+    }
+
+    pub(crate) fn element(&self, var: &EnumVar, field: &str) -> Result<Slot, SolverError> {
+        if let Some((_, _, slot)) = self.elements.borrow().iter().find(|(v, f, _)| *v == var.var && f == field) {
+            return Ok(slot.clone());
+        }
+        let mut var_type = None;
+        let mut cases = Vec::new();
+        for id in var.instances.iter() {
+            let value = self.get_object(*id).and_then(|obj| obj.get(field)).ok_or_else(|| SolverError::RuntimeError(format!("Unknown field `{}`", field)))?;
+            let Slot::Primitive(value) = value else {
+                return Err(SolverError::RuntimeError(format!("Field `{}` is not arithmetic", field)));
+            };
+            let Some(value) = value.clone().as_any().downcast_ref::<ArithVar>().map(|value| (value.var_type(), value.lin.clone())) else {
+                return Err(SolverError::RuntimeError(format!("Field `{}` is not arithmetic", field)));
+            };
+            var_type.get_or_insert(value.0);
+            cases.push((self.reify_ac(AcReif::Set(var.var, **id as i32)), value.1));
+        }
+        let Some(var_type) = var_type else {
+            return Err(SolverError::RuntimeError(format!("Cannot access field `{}` of an empty domain", field)));
+        };
+
+        let lin = Lin::from(self.lin.borrow_mut().add_var());
+        for (lit, value) in cases.iter() {
+            let guard = self.lin.borrow_mut().add_guard();
+            if !self.new_eq_constraint(&lin, value, Some(guard)) {
+                self.sat.borrow_mut().add_clause(vec![!*lit]).expect("Failed to add element clause");
+            }
+            self.link_lin_guard(*lit, guard);
+        }
+        // The bounds of the constant values still in the domain are bounds of the variable as well
+        for (_, value) in cases.iter() {
+            let Some((None, val)) = as_node(value) else { continue };
+            let above = |other: &Lin| as_node(other).is_none_or(|(var, other)| var.is_some() || other > val);
+            let below = |other: &Lin| as_node(other).is_none_or(|(var, other)| var.is_some() || other < val);
+            let mut le = vec![self.reify_le(&lin, value, false)];
+            le.extend(cases.iter().filter(|(_, other)| above(other)).map(|(lit, _)| *lit));
+            self.sat.borrow_mut().add_clause(le).expect("Failed to add element clause");
+            let mut ge = vec![self.reify_le(value, &lin, false)];
+            ge.extend(cases.iter().filter(|(_, other)| below(other)).map(|(lit, _)| *lit));
+            self.sat.borrow_mut().add_clause(ge).expect("Failed to add element clause");
+        }
+        self.element_links.borrow_mut().push((lin.clone(), cases));
+
+        let slot = Slot::Primitive(Rc::new(ArithVar::new(var_type, lin)));
+        self.elements.borrow_mut().push((var.var, field.to_string(), slot.clone()));
+        self.propagate()?;
+        Ok(slot)
+    }
+
+    fn propagate_elements(&self) -> bool {
+        let mut found = false;
+        for (lin, cases) in self.element_links.borrow().iter() {
+            let (lb, ub) = (self.arith_lb(lin), self.arith_ub(lin));
+            for (lit, value) in cases.iter() {
+                if self.sat.borrow().lit_value(lit) == LBool::Undef && (self.arith_lb(value) > ub || self.arith_ub(value) < lb) {
+                    self.enqueue(!*lit);
+                    found = true;
+                }
+            }
+        }
+        found
+    }
+
+    fn link_lin_guard(&self, lit: Lit, guard: linarith::GuardId) {
+        self.lin_reifs.borrow_mut().push((guard, lit));
+        let when = if lit == pos(lit.var()) { LBool::True } else { LBool::False };
+        let solver = self.slv.upgrade().expect("SolverState has been dropped");
+        self.sat.borrow_mut().add_listener(lit.var(), move |_var, val| {
+            if val != when {
+                return;
+            }
+            if let Err(conflict) = solver.lin.borrow_mut().assert(guard) {
+                trace!("Literal {} violates the linear constraints {:?}. Problem might be inconsistent.", lit, conflict);
+                solver.conflicts.borrow_mut().push((lit, Conflict::Lin(conflict)));
+                return;
+            }
+            if let Err(conflict) = solver.stn.borrow_mut().assert(guard) {
+                trace!("Literal {} closes a negative cycle through {:?}. Problem might be inconsistent.", lit, conflict);
+                solver.conflicts.borrow_mut().push((lit, Conflict::Lin(conflict)));
+            }
+        });
+    }
+
+    fn link_ac_constraint(&self, lit: Lit, constraint: ac3rm::ConstraintId) {
+        self.ac_reifs.borrow_mut().push((constraint, lit));
+        let when = if lit == pos(lit.var()) { LBool::True } else { LBool::False };
+        let solver = self.slv.upgrade().expect("SolverState has been dropped");
+        self.sat.borrow_mut().add_listener(lit.var(), move |_var, val| {
+            if val != when {
+                return;
+            }
+            if let Err(conflict) = solver.ac.borrow_mut().assert(constraint) {
+                trace!("Literal {} violates the AC constraints {:?}. Problem might be inconsistent.", lit, conflict);
+                solver.conflicts.borrow_mut().push((lit, Conflict::Ac(conflict)));
+            }
+        });
+    }
+
+    fn has_released(&self, lin: &Lin) -> bool {
+        let stn = self.stn.borrow();
+        lin.vars.keys().any(|var| stn.is_released(*var))
+    }
+
+    // Every variable the linear solver sees is released, so that the later constraints on it reach the linear solver as well
+    pub(crate) fn release_to_lin(&self, lin: &Lin) -> bool {
+        let mut constraints = Vec::new();
+        for var in lin.vars.keys() {
+            if !self.stn.borrow().is_released(*var) {
+                constraints.extend(self.stn.borrow_mut().release(*var));
+            }
+        }
+        if constraints.is_empty() {
+            return true;
+        }
+        let var_lin = |var: Option<linarith::VarId>| var.map_or(Lin::from(0), Lin::from);
+        let mut lin = self.lin.borrow_mut();
+        let mut guards = Vec::new();
+        for (to, from, weight, guard) in constraints {
+            // `to <= from + weight`
+            let mut right = var_lin(from);
+            right += &Lin::from(weight.val);
+            let posted = if weight.eps < 0 { lin.new_lt(&var_lin(to), &right, true, guard) } else { lin.new_le(&var_lin(to), &right, guard) };
+            if posted.is_err() {
+                return false;
+            }
+            if let Some(guard) = guard
+                && !guards.contains(&guard)
+            {
+                guards.push(guard);
+            }
+        }
+        // The guards of the active resolvers and of the true reified constraints have already been asserted
+        for (guard, _) in self.active_guards() {
+            if guards.contains(&guard) && lin.assert(guard).is_err() {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub(crate) fn arith_ub(&self, lin: &Lin) -> Rational {
+        let stn = self.stn.borrow();
+        let mut rest = Lin::from(lin.known_term);
+        let mut owned = Vec::new();
+        for (var, coeff) in lin.vars.iter() {
+            if stn.owns(*var) {
+                owned.push((*var, *coeff));
+            } else {
+                rest += &(&Lin::from(*var) * *coeff);
+            }
+        }
+        if owned.is_empty() {
+            return self.lin.borrow().ub(lin);
+        }
+        owned.sort_by(|(_, a), (_, b)| b.cmp(a));
+        if rest.vars.is_empty()
+            && let [(to, to_coeff), (from, from_coeff)] = owned.as_slice()
+            && *to_coeff == Rational::from(1)
+            && *from_coeff == Rational::from(-1)
+        {
+            return stn.distance(Some(*to), Some(*from)).val + rest.known_term;
+        }
+        let mut ub = self.lin.borrow().ub(&rest);
+        for (var, coeff) in owned {
+            ub = ub + coeff * if coeff > Rational::from(0) { stn.ub(var) } else { stn.lb(var) };
+        }
+        ub
+    }
+
+    pub(crate) fn arith_lb(&self, lin: &Lin) -> Rational {
+        -self.arith_ub(&-lin.clone())
+    }
+
+    pub(crate) fn arith_val(&self, lin: &Lin) -> Rational {
+        let stn = self.stn.borrow();
+        let mut rest = Lin::from(lin.known_term);
+        let mut val = Rational::from(0);
+        for (var, coeff) in lin.vars.iter() {
+            if stn.owns(*var) {
+                val = val + *coeff * stn.val(*var);
+            } else {
+                rest += &(&Lin::from(*var) * *coeff);
+            }
+        }
+        val + self.lin.borrow().val(&rest)
+    }
+
+    pub(crate) fn is_before(&self, left: &Lin, right: &Lin) -> bool {
+        let mut diff = left.clone();
+        diff += &(-right.clone());
+        self.arith_ub(&diff) <= Rational::from(0)
+    }
+
+    pub(crate) fn may_overlap(&self, a_start: &Lin, a_end: &Lin, b_start: &Lin, b_end: &Lin) -> bool {
+        !self.is_before(a_end, b_start) && !self.is_before(b_end, a_start)
+    }
+
+    pub(crate) fn add_unification(&self, rho: VarId, atom: AtomId, target: AtomId) {
+        self.unifications.borrow_mut().push((rho, atom, target));
+    }
+
+    pub(super) fn solution(&self) -> Solution {
+        let mut solution = Solution::default();
+        for atom_id in self.atoms.borrow().iter() {
+            let phi = self.flaws.borrow().get(*self.get_atom_flaw(*atom_id)).expect("Invalid flaw ID").phi();
+            if self.sat.borrow().value(phi) != LBool::True || self.sat.borrow().value(self.get_sigma(*atom_id)) != LBool::True {
+                continue; // The atom is either not part of the plan or unified with another atom
+            }
+            let atom = self.get_atom(*atom_id).expect("Atom should exist");
+            let mut args = BTreeMap::new();
+            let mut pred_q: VecDeque<Rc<Predicate>> = VecDeque::new();
+            pred_q.push_back(atom.predicate());
+            while let Some(pred) = pred_q.pop_front() {
+                for (_, name) in pred.args() {
+                    if let Some(val) = atom.get(name).and_then(|slot| self.slot_value(&slot)) {
+                        args.insert(name.to_string(), val);
+                    }
+                }
+                for super_pred in pred.parents() {
+                    pred_q.push_back(get_predicate_by_path(pred.as_ref(), super_pred).expect("Predicate should exist"));
+                }
+            }
+            solution.atoms.push(AtomSolution { id: **atom_id, predicate: atom.predicate().name().to_string(), fact: atom.is_fact(), args });
+        }
+        for (rho, atom, target) in self.unifications.borrow().iter() {
+            if self.sat.borrow().value(*rho) == LBool::True {
+                solution.unifications.push((**atom, **target));
+            }
+        }
+        for field in self.get_fields().iter().filter(|field| !self.prelude_fields.borrow().contains(field.name())) {
+            if let Some(val) = self.get(field.name()).and_then(|slot| self.slot_value(&slot)) {
+                solution.globals.insert(field.name().to_string(), val);
+            }
+        }
+        solution
+    }
+
+    pub(super) fn value_of(&self, path: &str) -> Result<VarValue, SolverError> {
+        let mut names = path.split('.').peekable();
+        let first = names.next().expect("Split always returns at least one item");
+        let mut slot = self.get(first).ok_or_else(|| SolverError::RuntimeError(format!("Unknown variable `{}`", first)))?;
+        while let Some(name) = names.next() {
+            slot = match slot {
+                Slot::ObjectRef(id) => self.get_object(id).and_then(|obj| obj.get(name)).ok_or_else(|| SolverError::RuntimeError(format!("Unknown field `{}` in `{}`", name, path)))?,
+                Slot::Primitive(var) => match var.as_any().downcast_ref::<EnumVar>() {
+                    Some(var) if names.peek().is_none() => return self.field_value(var, name),
+                    _ => return Err(SolverError::RuntimeError(format!("Cannot access field `{}` in `{}`: not an object", name, path))),
+                },
+                _ => return Err(SolverError::RuntimeError(format!("Cannot access field `{}` in `{}`: not an object", name, path))),
+            };
+        }
+        self.slot_value(&slot).ok_or_else(|| SolverError::RuntimeError(format!("`{}` is not a variable", path)))
+    }
+
+    /// Unlike `element`, it does not constrain the enum variable.
+    fn field_value(&self, var: &EnumVar, field: &str) -> Result<VarValue, SolverError> {
+        if let Some((_, _, slot)) = self.elements.borrow().iter().find(|(v, f, _)| *v == var.var && f == field) {
+            return self.slot_value(slot).ok_or_else(|| SolverError::RuntimeError(format!("Field `{}` is not arithmetic", field)));
+        }
+        let domain = self.ac.borrow().val(var.var);
+        let mut value: Option<(Rational, Rational, Rational)> = None;
+        for id in var.instances.iter().filter(|id| domain.contains(&(***id as i32))) {
+            let slot = self.get_object(*id).and_then(|obj| obj.get(field)).ok_or_else(|| SolverError::RuntimeError(format!("Unknown field `{}`", field)))?;
+            let Some(VarValue::Arith { lb, ub, val }) = self.slot_value(&slot) else {
+                return Err(SolverError::RuntimeError(format!("Field `{}` is not arithmetic", field)));
+            };
+            value = Some(match value {
+                Some((min, max, first)) => (if lb < min { lb } else { min }, if ub > max { ub } else { max }, first),
+                None => (lb, ub, val),
+            });
+        }
+        let (lb, ub, val) = value.ok_or_else(|| SolverError::RuntimeError(format!("Cannot access field `{}` of an empty domain", field)))?;
+        // The value of the field is only known once a single instance remains
+        if domain.len() == 1 { Ok(VarValue::Arith { lb, ub, val }) } else { Ok(VarValue::Bounds { lb, ub }) }
+    }
+
+    pub(crate) fn slot_value(&self, slot: &Slot) -> Option<VarValue> {
+        match slot {
+            Slot::Primitive(var) => {
+                let var = var.clone().as_any();
+                if let Some(var) = var.downcast_ref::<BoolVar>() {
+                    Some(VarValue::Bool(self.sat.borrow().lit_value(&var.lit)))
+                } else if let Some(var) = var.downcast_ref::<ArithVar>() {
+                    Some(VarValue::Arith { lb: self.arith_lb(&var.lin), ub: self.arith_ub(&var.lin), val: self.arith_val(&var.lin) })
+                } else if let Some(var) = var.downcast_ref::<EnumVar>() {
+                    Some(VarValue::Enum(self.ac.borrow().val(var.var).into_iter().map(|val| val as usize).collect()))
+                } else {
+                    var.downcast_ref::<StringVar>().map(|var| {
+                        let strings = self.strings.borrow();
+                        match var.var.get() {
+                            Some(var) => match self.ac.borrow().val(*var).as_slice() {
+                                [val] => VarValue::String(strings[*val as usize].clone()),
+                                vals => VarValue::Strings(vals.iter().map(|val| strings[*val as usize].clone()).collect()),
+                            },
+                            None => VarValue::Strings(strings.clone()),
+                        }
+                    })
+                }
+            }
+            Slot::ObjectRef(id) => Some(VarValue::Object(**id)),
+            _ => None,
+        }
+    }
+
     pub fn add_resolver(&self, flaw: &mut impl Flaw, resolver: Box<dyn Resolver>) {
         assert!(flaw.id() == resolver.flaw(), "Resolver {} does not resolve flaw {}", resolver.id(), flaw.id());
         let flaw_id = flaw.id();
         let resolver_id = resolver.id();
         trace!("Adding resolver: {} ({})", resolver_id, resolver.rho());
+        self.statistics.borrow_mut().resolvers += 1;
         let _ = self.tx_event.send(SolverEvent::NewResolver {
             resolver_id,
             rho: resolver.rho(),
@@ -183,6 +1276,7 @@ impl SolverState {
                 trace!("Active flaws count: {}", active_flaws.len());
             }
         }
+        self.trail_activity(resolver.flaw());
         let active_flaws = self.active_flaws.clone();
         let solver = self.slv.upgrade().expect("SolverState has been dropped");
         self.sat.borrow_mut().add_listener(resolver.rho(), {
@@ -197,9 +1291,9 @@ impl SolverState {
                                 Ok(_) => {
                                     trace!("Applied AC constraints for resolver {} successfully.", resolver_id);
                                 }
-                                Err(e) => {
-                                    trace!("Failed to apply AC constraints for resolver {} with error: {:?}. Problem might be inconsistent.", resolver_id, e);
-                                    solver.enqueue(neg(var));
+                                Err(conflict) => {
+                                    trace!("Failed to apply AC constraints for resolver {} with error: {:?}. Problem might be inconsistent.", resolver_id, conflict);
+                                    solver.conflicts.borrow_mut().push((pos(var), Conflict::Ac(conflict)));
                                     return;
                                 }
                             }
@@ -209,9 +1303,19 @@ impl SolverState {
                                 Ok(_) => {
                                     trace!("Applied linear constraint for resolver {} successfully.", resolver_id);
                                 }
-                                Err(e) => {
-                                    trace!("Failed to apply linear constraint for resolver {} with error: {:?}. Problem might be inconsistent.", resolver_id, e);
-                                    solver.enqueue(neg(var));
+                                Err(conflict) => {
+                                    trace!("Failed to apply linear constraint for resolver {} with error: {:?}. Problem might be inconsistent.", resolver_id, conflict);
+                                    solver.conflicts.borrow_mut().push((pos(var), Conflict::Lin(conflict)));
+                                    return;
+                                }
+                            }
+                            match solver.stn.borrow_mut().assert(lin_guard) {
+                                Ok(_) => {
+                                    trace!("Applied temporal constraints for resolver {} successfully.", resolver_id);
+                                }
+                                Err(conflict) => {
+                                    trace!("Applying temporal constraints for resolver {} closes a negative cycle through {:?}. Problem might be inconsistent.", resolver_id, conflict);
+                                    solver.conflicts.borrow_mut().push((pos(var), Conflict::Lin(conflict)));
                                     return;
                                 }
                             }
@@ -222,9 +1326,11 @@ impl SolverState {
                             trace!("Active flaws count: {}", active_flaws.len());
                         }
                         to_recompute.borrow_mut().remove(&flaw_id);
+                        solver.trail_activity(flaw_id);
                     }
                     LBool::False => {
                         to_recompute.borrow_mut().insert(flaw_id);
+                        solver.trail_activity(flaw_id);
                     }
                     LBool::Undef => {}
                 }
@@ -239,6 +1345,17 @@ impl SolverState {
         self.resolvers.borrow_mut().push(resolver);
     }
 
+    fn current_cause(&self) -> (Lit, Option<ResolverId>) {
+        match *self.c_res.borrow() {
+            Some(res_id) => (pos(self.resolvers.borrow().get(*res_id).expect("Invalid resolver ID").rho()), Some(res_id)),
+            None => (TRUE_LIT, None),
+        }
+    }
+
+    pub fn get_flaws_len(&self) -> usize {
+        self.flaws.borrow().len()
+    }
+
     pub fn get_resolvers_len(&self) -> usize {
         self.resolvers.borrow().len()
     }
@@ -262,86 +1379,126 @@ impl SolverState {
     fn build_graph(&self) -> Result<(), SolverError> {
         info!("Building graph...");
         while self.active_flaws.borrow().iter().any(|flaw| self.flaws.borrow().get(**flaw).expect("Invalid flaw ID").cost().is_infinite()) {
-            if let Some(flaw_id) = self.flaw_q.borrow_mut().pop_front() {
-                self.set_current_flaw(Some(flaw_id));
-                let mut flaw = {
-                    let mut flaws = self.flaws.borrow_mut();
-                    let flaw = flaws.get_mut(*flaw_id).expect("Invalid flaw ID");
-                    std::mem::replace(flaw, Box::new(ContextFlaw {}))
-                };
-
-                trace!("Expanding flaw {} ({})", flaw_id, flaw.phi());
-                flaw.compute_resolvers();
-                let phi = flaw.phi();
-                let resolvers = flaw.resolvers();
-
-                {
-                    let mut flaws = self.flaws.borrow_mut();
-                    let ctx_flaw = flaws.get_mut(*flaw_id).expect("Invalid flaw ID");
-                    *ctx_flaw = flaw;
-                }
+            self.check_limits()?;
+            let next = self.flaw_q.borrow_mut().pop_front();
+            if let Some(flaw_id) = next {
+                self.expand_flaw(flaw_id)?;
+            } else {
+                return Err(SolverError::RuntimeError("No more active flaws to expand, but some flaws have infinite cost. No solution found.".into()));
+            }
+        }
+        Ok(())
+    }
 
-                let mut causal_constraint = vec![neg(phi)];
-                for res_id in resolvers {
-                    self.set_current_resolver(Some(res_id));
-                    let mut resolver = {
-                        let mut resolvers = self.resolvers.borrow_mut();
-                        let resolver = resolvers.get_mut(*res_id).expect("Invalid resolver ID");
-                        std::mem::replace(resolver, Box::new(ContextRes::new(resolver.rho(), resolver.lin_guard())))
-                    };
+    fn expand_flaw(&self, flaw_id: FlawId) -> Result<(), SolverError> {
+        self.statistics.borrow_mut().graph_iterations += 1;
+        self.set_current_flaw(Some(flaw_id));
+        let mut flaw = {
+            let mut flaws = self.flaws.borrow_mut();
+            let flaw = flaws.get_mut(*flaw_id).expect("Invalid flaw ID");
+            std::mem::replace(flaw, Box::new(ContextFlaw {}))
+        };
+
+        trace!("Expanding flaw {} ({})", flaw_id, flaw.phi());
+        flaw.compute_resolvers();
+        let phi = flaw.phi();
+        let resolvers = flaw.resolvers();
+
+        {
+            let mut flaws = self.flaws.borrow_mut();
+            let ctx_flaw = flaws.get_mut(*flaw_id).expect("Invalid flaw ID");
+            *ctx_flaw = flaw;
+        }
 
-                    trace!("Applying resolver {} ({})", res_id, resolver.rho());
-                    if let Err(_) = resolver.apply() {
-                        trace!("Resolver {} is not applicable, deactivating.", res_id);
-                        if let Err(_) = self.sat.borrow_mut().add_clause(vec![neg(resolver.rho())]) {
-                            warn!("Failed to add clause for resolver {}, problem is inconsistent", res_id);
-                            return Err(SolverError::Inconsistent);
-                        }
-                        continue;
-                    }
-                    let rho = resolver.rho();
-                    if rho != phi {
-                        causal_constraint.push(pos(rho));
-                    }
+        let mut causal_constraint = vec![neg(phi)];
+        for res_id in resolvers {
+            self.set_current_resolver(Some(res_id));
+            let mut resolver = {
+                let mut resolvers = self.resolvers.borrow_mut();
+                let resolver = resolvers.get_mut(*res_id).expect("Invalid resolver ID");
+                std::mem::replace(resolver, Box::new(ContextRes::new(resolver.rho(), resolver.lin_guard())))
+            };
 
-                    {
-                        let mut resolvers = self.resolvers.borrow_mut();
-                        let ctx_res = resolvers.get_mut(*res_id).expect("Invalid resolver ID");
-                        if let Some(constraints) = ctx_res.ac_constraints() {
-                            for constraint in constraints {
-                                resolver.add_ac_constraint(constraint);
-                            }
-                        }
-                        for requirement in resolver.requirements() {
-                            resolver.add_requirement(requirement);
-                        }
-                        *ctx_res = resolver;
+            trace!("Applying resolver {} ({})", res_id, resolver.rho());
+            self.statistics.borrow_mut().applied_resolvers += 1;
+            let applied = resolver.apply().is_ok();
+            let rho = resolver.rho();
+
+            {
+                let mut resolvers = self.resolvers.borrow_mut();
+                let ctx_res = resolvers.get_mut(*res_id).expect("Invalid resolver ID");
+                if let Some(constraints) = ctx_res.ac_constraints() {
+                    for constraint in constraints {
+                        resolver.add_ac_constraint(constraint);
                     }
-
-                    self.propagate()?;
-                    self.set_current_resolver(None);
                 }
+                for requirement in ctx_res.requirements() {
+                    resolver.add_requirement(requirement);
+                }
+                *ctx_res = resolver;
+            }
 
-                if causal_constraint.len() > 1
-                    && let Err(_) = self.sat.borrow_mut().add_clause(causal_constraint)
-                {
-                    return Err(SolverError::RuntimeError(format!("Failed to add causal constraint for flaw {}", flaw_id)));
+            self.create_string_domains();
+            self.create_capacity_links();
+            if let Some(err) = self.reify_error.take() {
+                return Err(err);
+            }
+
+            if !applied {
+                trace!("Resolver {} is not applicable, deactivating.", res_id);
+                if self.sat.borrow_mut().add_clause(vec![neg(rho)]).is_err() {
+                    warn!("Failed to add clause for resolver {}, problem is inconsistent", res_id);
+                    return Err(SolverError::Inconsistent);
                 }
-                self.propagate()?;
-                self.compute_flaw_cost(flaw_id);
-                self.set_current_flaw(None);
-            } else {
-                return Err(SolverError::RuntimeError("No more active flaws to expand, but some flaws have infinite cost. No solution found.".into()));
+                self.set_current_resolver(None);
+                continue;
+            }
+            if rho != phi {
+                causal_constraint.push(pos(rho));
             }
+
+            self.propagate()?;
+            self.set_current_resolver(None);
         }
+
+        if causal_constraint.len() > 1
+            && let Err(_) = self.sat.borrow_mut().add_clause(causal_constraint)
+        {
+            return Err(SolverError::RuntimeError(format!("Failed to add causal constraint for flaw {}", flaw_id)));
+        }
+        self.propagate()?;
+        self.compute_flaw_cost(flaw_id);
+        self.set_current_flaw(None);
         Ok(())
     }
 
+    fn detect_flaws(&self) -> Result<bool, SolverError> {
+        let mut found = false;
+        for timeline in self.timelines.iter() {
+            found |= !timeline.check().is_empty();
+        }
+        found |= !self.check_integrality().is_empty();
+        found |= !self.check_products()?.is_empty();
+        loop {
+            let next = self.flaw_q.borrow_mut().pop_front();
+            let Some(flaw_id) = next else { break };
+            self.expand_flaw(flaw_id)?;
+        }
+        Ok(found)
+    }
+
     fn propagate(&self) -> Result<(), SolverError> {
+        self.statistics.borrow_mut().propagations += 1;
         loop {
+            self.explain_conflicts();
+            if !self.add_learnts() {
+                warn!("Learnt clause violated during propagation, problem is inconsistent");
+                return Err(SolverError::Inconsistent);
+            }
             let next = self.prop_q.borrow_mut().pop_front();
             let next_lit = match next {
                 Some(lit) => lit,
+                None if self.propagate_ac_links() || self.propagate_domain_links() || self.propagate_elements() => continue,
                 None => break,
             };
             let lit_val = self.sat.borrow().lit_value(&next_lit);
@@ -375,16 +1532,19 @@ impl SolverState {
         let mut stack: Vec<(FlawId, HashSet<FlawId>)> = vec![(flaw_id, HashSet::new())];
 
         let resolvers = self.resolvers.borrow();
-        while let Some((flaw, mut visited)) = stack.pop() {
+        while let Some((flaw_id, mut visited)) = stack.pop() {
+            if !visited.insert(flaw_id) {
+                continue; // The flaw is already being updated along this causal chain
+            }
             let mut current_cost = Rational::POSITIVE_INFINITY;
 
             let (phi, resolver_ids, old_cost, supports) = {
                 let flaws = self.flaws.borrow();
-                let flaw = flaws.get(*flaw).expect("Invalid flaw ID");
+                let flaw = flaws.get(*flaw_id).expect("Invalid flaw ID");
                 (flaw.phi(), flaw.resolvers(), flaw.cost(), flaw.supports())
             };
 
-            if self.sat.borrow().value(phi) != LBool::False && visited.insert(flaw_id) {
+            if self.sat.borrow().value(phi) != LBool::False {
                 for resolver_id in resolver_ids {
                     let resolver = resolvers.get(*resolver_id).expect("Invalid resolver ID");
                     if self.sat.borrow().value(resolver.rho()) != LBool::False {
@@ -398,6 +1558,7 @@ impl SolverState {
 
             if old_cost != current_cost {
                 trace!("Updating cost for flaw {} from {} to {}", flaw_id, old_cost, current_cost);
+                self.statistics.borrow_mut().cost_updates += 1;
                 self.flaws.borrow_mut().get_mut(*flaw_id).expect("Invalid flaw ID").set_cost(current_cost);
                 let _ = self.tx_event.send(SolverEvent::FlawCostUpdate { flaw_id, cost: current_cost });
                 for support in supports {
@@ -411,19 +1572,87 @@ impl SolverState {
     fn compute_resolver_cost(&self, resolver: ResolverId) -> Rational {
         let resolvers = self.resolvers.borrow();
         let resolver = resolvers.get(*resolver).expect("Invalid resolver ID");
-        resolver.requirements().iter().map(|flaw| self.flaws.borrow().get(**flaw).expect("Invalid resolver requirement").cost()).fold(resolver.intrinsic_cost(), |max_cost, c| if c > max_cost { c } else { max_cost })
-    }
-
-    fn get_most_expensive_flaw(&self) -> Option<FlawId> {
         let flaws = self.flaws.borrow();
-        self.active_flaws.borrow().iter().max_by_key(|flaw| flaws.get(***flaw).expect("Invalid flaw ID").cost()).copied()
+        let requirement_costs = resolver.requirements().into_iter().map(|flaw| flaws.get(*flaw).expect("Invalid resolver requirement").cost());
+        match *self.cost_heuristic.borrow() {
+            CostHeuristic::Max => requirement_costs.fold(resolver.intrinsic_cost(), |max_cost, c| if c > max_cost { c } else { max_cost }),
+            CostHeuristic::Add => requirement_costs.fold(resolver.intrinsic_cost(), |sum, c| sum + c),
+            CostHeuristic::FastForward => {
+                // Sums the intrinsic costs of the cheapest resolvers of the (transitively) required flaws, counting each flaw once
+                let mut cost = resolver.intrinsic_cost();
+                let mut visited: HashSet<FlawId> = HashSet::new();
+                let mut flaw_q: VecDeque<FlawId> = resolver.requirements().into_iter().collect();
+                while let Some(flaw_id) = flaw_q.pop_front() {
+                    if !visited.insert(flaw_id) {
+                        continue;
+                    }
+                    let flaw = flaws.get(*flaw_id).expect("Invalid resolver requirement");
+                    if flaw.cost().is_infinite() {
+                        return Rational::POSITIVE_INFINITY;
+                    }
+                    if let Some(best) = flaw
+                        .resolvers()
+                        .into_iter()
+                        .map(|res_id| resolvers.get(*res_id).expect("Invalid resolver ID"))
+                        .filter(|res| self.sat.borrow().value(res.rho()) != LBool::False)
+                        .min_by_key(|res| res.requirements().into_iter().map(|req| flaws.get(*req).expect("Invalid resolver requirement").cost()).fold(res.intrinsic_cost(), |max_cost, c| if c > max_cost { c } else { max_cost }))
+                    {
+                        cost = cost + best.intrinsic_cost();
+                        flaw_q.extend(best.requirements());
+                    }
+                }
+                cost
+            }
+        }
     }
 
-    fn get_least_expensive_resolver(&self, flaw_id: FlawId) -> Option<ResolverId> {
-        let resolvers = self.resolvers.borrow();
+    fn flaw_info(&self, flaw_id: FlawId) -> FlawInfo {
         let flaws = self.flaws.borrow();
+        let resolvers = self.resolvers.borrow();
         let flaw = flaws.get(*flaw_id).expect("Invalid flaw ID");
-        flaw.resolvers().iter().filter_map(|res_id| if self.sat.borrow().value(resolvers.get(**res_id).expect("Invalid resolver ID").rho()) != LBool::False { Some((res_id, self.compute_resolver_cost(*res_id))) } else { None }).min_by_key(|(_, cost)| *cost).map(|(res_id, _)| *res_id)
+        let live_resolvers = flaw.resolvers().iter().filter(|res_id| self.sat.borrow().value(resolvers.get(***res_id).expect("Invalid resolver ID").rho()) != LBool::False).count();
+        FlawInfo {
+            id: flaw_id,
+            kind: flaw.kind(),
+            cost: flaw.cost(),
+            depth: self.flaw_depths.borrow()[*flaw_id],
+            live_resolvers,
+        }
+    }
+
+    fn select_flaw(&self) -> Option<FlawId> {
+        let mut candidates = self.active_flaws.borrow().iter().map(|flaw_id| self.flaw_info(*flaw_id)).collect::<Vec<_>>();
+        if candidates.is_empty() {
+            return None;
+        }
+        candidates.sort_by_key(|flaw| *flaw.id);
+        self.flaw_selector.borrow_mut().select_flaw(&candidates)
+    }
+
+    fn select_resolver(&self, flaw_id: FlawId) -> Option<ResolverId> {
+        let flaw = self.flaw_info(flaw_id);
+        let candidates = {
+            let resolvers = self.resolvers.borrow();
+            let flaws = self.flaws.borrow();
+            flaws
+                .get(*flaw_id)
+                .expect("Invalid flaw ID")
+                .resolvers()
+                .iter()
+                .map(|res_id| resolvers.get(**res_id).expect("Invalid resolver ID"))
+                .filter(|res| self.sat.borrow().value(res.rho()) != LBool::False)
+                .map(|res| ResolverInfo {
+                    id: res.id(),
+                    kind: res.kind(),
+                    intrinsic_cost: res.intrinsic_cost(),
+                    cost: self.compute_resolver_cost(res.id()),
+                })
+                .collect::<Vec<_>>()
+        };
+        if candidates.is_empty() {
+            return None;
+        }
+        self.resolver_selector.borrow_mut().select_resolver(&flaw, &candidates)
     }
 }
 
@@ -436,6 +1665,9 @@ impl Flaw for ContextFlaw {
     fn id(&self) -> FlawId {
         panic!("ContextFlaw::id should not be called")
     }
+    fn kind(&self) -> FlawKind {
+        panic!("ContextFlaw::kind should not be called")
+    }
     fn phi(&self) -> VarId {
         panic!("ContextFlaw::phi should not be called")
     }
@@ -480,12 +1712,13 @@ impl ToJson for ContextFlaw {
 struct ContextRes {
     rho: VarId,
     lin_guard: Option<linarith::GuardId>,
+    requirements: Vec<FlawId>,
     ac_constraints: Vec<ac3rm::ConstraintId>,
 }
 
 impl ContextRes {
     fn new(rho: VarId, lin_guard: Option<linarith::GuardId>) -> Self {
-        Self { rho, lin_guard, ac_constraints: Vec::new() }
+        Self { rho, lin_guard, requirements: Vec::new(), ac_constraints: Vec::new() }
     }
 }
 
@@ -498,6 +1731,10 @@ impl Resolver for ContextRes {
         panic!("ResolverPlaceholder::id should not be called")
     }
 
+    fn kind(&self) -> ResolverKind {
+        panic!("ResolverPlaceholder::kind should not be called")
+    }
+
     fn flaw(&self) -> FlawId {
         panic!("ResolverPlaceholder::flaw should not be called")
     }
@@ -514,6 +1751,14 @@ impl Resolver for ContextRes {
         panic!("ResolverPlaceholder::apply should not be called")
     }
 
+    fn requirements(&self) -> Vec<FlawId> {
+        self.requirements.clone()
+    }
+
+    fn add_requirement(&mut self, flaw_id: FlawId) {
+        self.requirements.push(flaw_id);
+    }
+
     fn ac_constraints(&self) -> Option<Vec<ac3rm::ConstraintId>> {
         Some(self.ac_constraints.clone())
     }
@@ -567,7 +1812,15 @@ impl Env for SolverState {
     }
 
     fn get(&self, name: &str) -> Option<Slot> {
-        self.core.get(name)
+        // The fields accessed through object variables are element variables, so that the constraints on them prune the object variables
+        let Some((path, field)) = name.rsplit_once('.') else {
+            return self.core.get(name);
+        };
+        match self.get(path)? {
+            Slot::ObjectRef(id) => self.get_object(id).and_then(|obj| obj.get(field)),
+            Slot::Primitive(var) => var.as_any().downcast_ref::<EnumVar>().and_then(|var| self.element(var, field).ok()),
+            _ => None,
+        }
     }
 
     fn set(&self, name: String, value: Slot) {
@@ -580,13 +1833,20 @@ impl Core for SolverState {
         Slot::Primitive(Rc::new(BoolVar::new(self.bool_type(), if value { TRUE_LIT } else { FALSE_LIT })))
     }
     fn new_bool_var(&self) -> Slot {
-        Slot::Primitive(Rc::new(BoolVar::new(self.bool_type(), pos(self.sat.borrow_mut().add_var()))))
+        let lit = pos(self.sat.borrow_mut().add_var());
+        let (rho, cause) = self.current_cause();
+        let flaw_id = FlawId(self.flaws.borrow().len());
+        self.add_flaw(BoolFlaw::new(self.slv.clone(), flaw_id, rho.var(), cause, lit));
+        Slot::Primitive(Rc::new(BoolVar::new(self.bool_type(), lit)))
     }
     fn new_int(&self, value: i64) -> Slot {
         Slot::Primitive(Rc::new(ArithVar::new(self.int_type(), Lin::from(value))))
     }
     fn new_int_var(&self) -> Slot {
-        Slot::Primitive(Rc::new(ArithVar::new(self.int_type(), Lin::from(self.lin.borrow_mut().add_var()))))
+        let var = self.lin.borrow_mut().add_var();
+        let (rho, _) = self.current_cause();
+        self.int_vars.borrow_mut().push((var, rho.var()));
+        Slot::Primitive(Rc::new(ArithVar::new(self.int_type(), Lin::from(var))))
     }
     fn new_real(&self, num: i64, den: i64) -> Slot {
         Slot::Primitive(Rc::new(ArithVar::new(self.real_type(), Lin::from(Rational::new(num, den)))))
@@ -595,10 +1855,16 @@ impl Core for SolverState {
         Slot::Primitive(Rc::new(ArithVar::new(self.real_type(), Lin::from(self.lin.borrow_mut().add_var()))))
     }
     fn new_string(&self, value: &str) -> Slot {
-        Slot::Primitive(Rc::new(StringVar::new(self.string_type(), value.to_string())))
+        let val = self.intern(value);
+        Slot::Primitive(Rc::new(StringVar::new(self.string_type(), Some(self.ac.borrow_mut().add_var(vec![val])))))
     }
     fn new_string_var(&self) -> Slot {
-        Slot::Primitive(Rc::new(StringVar::new(self.string_type(), String::new())))
+        let var = Rc::new(StringVar::new(self.string_type(), None));
+        self.pending_strings.borrow_mut().push(var.clone());
+        let (rho, cause) = self.current_cause();
+        let flaw_id = FlawId(self.flaws.borrow().len());
+        self.add_flaw(StringFlaw::new(self.slv.clone(), flaw_id, rho.var(), cause, var.clone()));
+        Slot::Primitive(var)
     }
 
     fn sum(&self, sum: &[Slot]) -> Result<Slot, RiddleError> {
@@ -631,6 +1897,7 @@ impl Core for SolverState {
         }
     }
     fn mul(&self, mul: &[Slot]) -> Result<Slot, RiddleError> {
+        let int = arith_type(self, mul)?.name() == "int";
         let mut result = Lin::from(1);
         for var in mul {
             match var {
@@ -641,16 +1908,16 @@ impl Core for SolverState {
                         } else if var.lin.vars.is_empty() {
                             result = &result * var.lin.known_term;
                         } else {
-                            return Err(RiddleError::RuntimeError("Non-linear multiplication is not supported".to_string()));
+                            result = self.new_product(result, var.lin.clone(), int);
                         }
                     } else {
-                        panic!("Expected int or real");
+                        return Err(RiddleError::RuntimeError("Expected int or real".to_string()));
                     };
                 }
                 _ => return Err(RiddleError::RuntimeError("Expected int or real".to_string())),
             }
         }
-        if arith_type(self, mul)?.name() == "int" { Ok(Slot::Primitive(Rc::new(ArithVar::new(self.int_type(), result)))) } else { Ok(Slot::Primitive(Rc::new(ArithVar::new(self.real_type(), result)))) }
+        if int { Ok(Slot::Primitive(Rc::new(ArithVar::new(self.int_type(), result)))) } else { Ok(Slot::Primitive(Rc::new(ArithVar::new(self.real_type(), result)))) }
     }
     fn div(&self, left: Slot, right: Slot) -> Result<Slot, RiddleError> {
         match (left, right) {
@@ -713,18 +1980,12 @@ impl Core for SolverState {
                             && self.sat.borrow().value(rho) == LBool::Undef
                             && let lin_guard = lin_guard.flatten().expect("Current resolver should have a linear guard")
                         {
-                            self.lin.borrow_mut().new_eq(left_lin, right_lin, Some(lin_guard)).is_ok()
+                            self.new_eq_constraint(left_lin, right_lin, Some(lin_guard))
                         } else {
-                            self.lin.borrow_mut().new_eq(left_lin, right_lin, None).is_ok()
-                        }
-                    } else if let (Some(left), Some(right)) = (left.clone().as_any().downcast_ref::<StringVar>(), right.clone().as_any().downcast_ref::<StringVar>()) {
-                        if let Some(rho) = rho
-                            && self.sat.borrow().value(rho) == LBool::Undef
-                        {
-                            self.sat.borrow_mut().add_clause(vec![neg(rho)]).is_ok()
-                        } else {
-                            left.value == right.value
+                            self.new_eq_constraint(left_lin, right_lin, None)
                         }
+                    } else if let (Ok(left), Ok(right)) = (left.clone().as_any().downcast::<StringVar>(), right.clone().as_any().downcast::<StringVar>()) {
+                        self.new_string_constraint(left, right, true, rho)
                     } else if let (Some(left), Some(right)) = (left.clone().as_any().downcast_ref::<EnumVar>(), right.clone().as_any().downcast_ref::<EnumVar>()) {
                         let constraint_id = self.ac.borrow_mut().new_constraint(ac3rm::Constraint::Equality(left.var, right.var));
                         if let Some(rho) = rho
@@ -781,6 +2042,7 @@ impl Core for SolverState {
                         false
                     }
                 }
+                (Slot::ObjectRef(left), Slot::ObjectRef(right)) if left == right => true,
                 _ => {
                     if let Some(rho) = rho
                         && self.sat.borrow().value(rho) == LBool::Undef
@@ -798,9 +2060,9 @@ impl Core for SolverState {
                     && self.sat.borrow().value(rho) == LBool::Undef
                     && let lin_guard = lin_guard.flatten().expect("Current resolver should have a linear guard")
                 {
-                    self.lin.borrow_mut().new_lt(&left_lin, &right_lin, true, Some(lin_guard)).is_ok()
+                    self.new_le_constraint(&left_lin, &right_lin, true, Some(lin_guard))
                 } else {
-                    self.lin.borrow_mut().new_lt(&left_lin, &right_lin, true, None).is_ok()
+                    self.new_le_constraint(&left_lin, &right_lin, true, None)
                 }
             }
             BoolExpr::Leq { left, right, .. } => {
@@ -810,28 +2072,19 @@ impl Core for SolverState {
                     && self.sat.borrow().value(rho) == LBool::Undef
                     && let lin_guard = lin_guard.flatten().expect("Current resolver should have a linear guard")
                 {
-                    self.lin.borrow_mut().new_le(&left_lin, &right_lin, Some(lin_guard)).is_ok()
+                    self.new_le_constraint(&left_lin, &right_lin, false, Some(lin_guard))
                 } else {
-                    self.lin.borrow_mut().new_le(&left_lin, &right_lin, None).is_ok()
+                    self.new_le_constraint(&left_lin, &right_lin, false, None)
                 }
             }
             BoolExpr::Or { terms, .. } => {
-                let lits: Vec<Lit> = terms
-                    .iter()
-                    .map(|term| match term.as_ref() {
-                        BoolExpr::Term { term, .. } => match term {
-                            Slot::Primitive(var) => {
-                                if let Some(bool_var) = var.clone().as_any().downcast_ref::<BoolVar>() {
-                                    bool_var.lit
-                                } else {
-                                    panic!("Expected BoolVar");
-                                }
-                            }
-                            _ => panic!("Expected BoolExpr::Term with a BoolVar"),
-                        },
-                        _ => panic!("Expected BoolExpr::Term"),
-                    })
-                    .collect();
+                let lits = match terms.iter().map(|term| self.reify(term)).collect::<Result<Vec<Lit>, _>>() {
+                    Ok(lits) => lits,
+                    Err(err) => {
+                        self.reify_error.replace(Some(err));
+                        return false;
+                    }
+                };
                 let (phi, cause) = if let Some(rho) = rho
                     && self.sat.borrow().value(rho) == LBool::Undef
                 {
@@ -875,18 +2128,21 @@ impl Core for SolverState {
                                 self.sat.borrow_mut().add_clause(vec![!left_lit, !right_lit]).is_ok()
                             }
                         } else if let (Some(_left), Some(_right)) = (left_v.clone().as_any().downcast_ref::<ArithVar>(), right_v.clone().as_any().downcast_ref::<ArithVar>()) {
-                            self.assert(Rc::new(BoolExpr::Or {
-                                var_type: Rc::downgrade(&self.bool_type()),
-                                terms: vec![Rc::new(BoolExpr::Lt { var_type: Rc::downgrade(&self.bool_type()), left: left.clone(), right: right.clone() }), Rc::new(BoolExpr::Lt { var_type: Rc::downgrade(&self.bool_type()), left: left.clone(), right: right.clone() })],
-                            }))
-                        } else if let (Some(left), Some(right)) = (left_v.clone().as_any().downcast_ref::<StringVar>(), right_v.clone().as_any().downcast_ref::<StringVar>()) {
-                            if let Some(rho) = rho
+                            let (phi, cause) = if let Some(rho) = rho
                                 && self.sat.borrow().value(rho) == LBool::Undef
                             {
-                                self.sat.borrow_mut().add_clause(vec![neg(rho)]).is_ok()
+                                (pos(rho), Some(self.c_res.borrow().unwrap()))
                             } else {
-                                left.value != right.value
+                                (TRUE_LIT, None)
+                            };
+                            let flaw_id = FlawId(self.flaws.borrow().len());
+                            self.add_flaw(DisequalityFlaw::new(self.slv.clone(), flaw_id, phi.var(), cause, left.clone(), right.clone()));
+                            if let Some(res_id) = cause {
+                                self.resolvers.borrow_mut().get_mut(*res_id).expect("Invalid resolver ID").add_requirement(flaw_id);
                             }
+                            true
+                        } else if let (Ok(left), Ok(right)) = (left_v.clone().as_any().downcast::<StringVar>(), right_v.clone().as_any().downcast::<StringVar>()) {
+                            self.new_string_constraint(left, right, false, rho)
                         } else if let (Some(left), Some(right)) = (left_v.clone().as_any().downcast_ref::<EnumVar>(), right_v.clone().as_any().downcast_ref::<EnumVar>()) {
                             let constraint_id = self.ac.borrow_mut().new_constraint(ac3rm::Constraint::Inequality(left.var, right.var));
                             if let Some(rho) = rho
@@ -939,6 +2195,15 @@ impl Core for SolverState {
                             false
                         }
                     }
+                    (Slot::ObjectRef(left), Slot::ObjectRef(right)) if left == right => {
+                        if let Some(rho) = rho
+                            && self.sat.borrow().value(rho) == LBool::Undef
+                        {
+                            self.sat.borrow_mut().add_clause(vec![neg(rho)]).is_ok()
+                        } else {
+                            false
+                        }
+                    }
                     _ => true,
                 },
                 BoolExpr::Lt { left, right, .. } => {
@@ -948,9 +2213,9 @@ impl Core for SolverState {
                         && self.sat.borrow().value(rho) == LBool::Undef
                         && let lin_guard = lin_guard.flatten().expect("Current resolver should have a linear guard")
                     {
-                        self.lin.borrow_mut().new_ge(&left_lin, &right_lin, Some(lin_guard)).is_ok()
+                        self.new_le_constraint(&right_lin, &left_lin, false, Some(lin_guard))
                     } else {
-                        self.lin.borrow_mut().new_ge(&left_lin, &right_lin, None).is_ok()
+                        self.new_le_constraint(&right_lin, &left_lin, false, None)
                     }
                 }
                 BoolExpr::Leq { left, right, .. } => {
@@ -960,12 +2225,27 @@ impl Core for SolverState {
                         && self.sat.borrow().value(rho) == LBool::Undef
                         && let lin_guard = lin_guard.flatten().expect("Current resolver should have a linear guard")
                     {
-                        self.lin.borrow_mut().new_gt(&left_lin, &right_lin, true, Some(lin_guard)).is_ok()
+                        self.new_le_constraint(&right_lin, &left_lin, true, Some(lin_guard))
                     } else {
-                        self.lin.borrow_mut().new_gt(&left_lin, &right_lin, true, None).is_ok()
+                        self.new_le_constraint(&right_lin, &left_lin, true, None)
+                    }
+                }
+                term => {
+                    let lit = match self.reify(term) {
+                        Ok(lit) => lit,
+                        Err(err) => {
+                            self.reify_error.replace(Some(err));
+                            return false;
+                        }
+                    };
+                    if let Some(rho) = rho
+                        && self.sat.borrow().value(rho) == LBool::Undef
+                    {
+                        self.sat.borrow_mut().add_clause(vec![neg(rho), !lit]).is_ok()
+                    } else {
+                        self.sat.borrow_mut().add_clause(vec![!lit]).is_ok()
                     }
                 }
-                _ => panic!("Expected BoolExpr::Term, BoolExpr::Eq, BoolExpr::Lt, or BoolExpr::Leq"),
             },
         }
     }
@@ -973,39 +2253,54 @@ impl Core for SolverState {
     fn new_var(&self, class: Rc<dyn Class>, instances: &[ObjectId]) -> Result<Slot, RiddleError> {
         let vals = instances.iter().map(|id| **id as i32).collect::<Vec<_>>();
         let var = self.ac.borrow_mut().add_var(vals);
-        let var = Rc::new(EnumVar::new(class, var));
-        let resolvers = self.resolvers.borrow();
-        let c_res = self.c_res.borrow().map_or(None, |res_id| resolvers.get(*res_id).map(|res| res.as_ref()));
-        let rho = c_res.map_or(watchsat::TRUE_LIT, |res| pos(res.rho()));
-        let cause = c_res.map(|res| res.id());
+        let var = Rc::new(EnumVar::new(class, var, instances.to_vec()));
+        let (rho, cause) = self.current_cause();
         let flaw_id = FlawId(self.flaws.borrow().len());
-        self.add_flaw(EnumFlaw::new(self.slv.clone(), flaw_id, rho.var(), cause, var.clone()));
+        self.add_flaw(EnumFlaw::new(self.slv.clone(), flaw_id, rho.var(), cause, var));
         Ok(Slot::Primitive(var))
     }
 
-    fn new_disjunction(&self, _disjunction: Disjunction) {
-        unimplemented!()
+    fn new_disjunction(&self, disjunction: Disjunction) {
+        let (rho, cause) = self.current_cause();
+        let flaw_id = FlawId(self.flaws.borrow().len());
+        self.add_flaw(DisjunctionFlaw::new(self.slv.clone(), flaw_id, rho.var(), cause, disjunction));
+        if let Some(res_id) = cause {
+            self.resolvers.borrow_mut().get_mut(*res_id).expect("Invalid resolver ID").add_requirement(flaw_id);
+        }
     }
 
     fn new_object(&self, class: Rc<dyn Class>) -> ObjectId {
-        self.core.new_object(class)
+        let id = self.core.new_object(class.clone());
+        for timeline in self.timelines.iter() {
+            if timeline.handles(&class) {
+                timeline.add_object(id);
+            }
+        }
+        id
     }
     fn get_object(&self, id: ObjectId) -> Option<Rc<Object>> {
         self.core.get_object(id)
     }
     fn new_atom(&self, predicate: Rc<Predicate>, fact: bool, args: HashMap<String, Slot>) -> AtomId {
         let atm = self.core.new_atom(predicate, fact, args);
-        let resolvers = self.resolvers.borrow();
-        let c_res = self.c_res.borrow().map_or(None, |res_id| resolvers.get(*res_id).map(|res| res.as_ref()));
-        let rho = c_res.map_or(watchsat::TRUE_LIT, |res| pos(res.rho()));
-        let cause = c_res.map(|res| res.id());
+        self.atoms.borrow_mut().push(atm);
+        let (rho, cause) = self.current_cause();
         let flaw_id = FlawId(self.flaws.borrow().len());
         self.atom_flaws.borrow_mut().push(flaw_id);
         let sigma = self.sat.borrow_mut().add_var();
         self.atom_sigmas.borrow_mut().push(sigma);
         self.add_flaw(AtomFlaw::new(self.slv.clone(), flaw_id, rho.var(), cause, atm));
-        if let Some(res) = c_res {
-            self.resolvers.borrow_mut().get_mut(*res.id()).expect("Invalid resolver ID").add_requirement(flaw_id);
+        if let Some(res_id) = cause {
+            self.resolvers.borrow_mut().get_mut(*res_id).expect("Invalid resolver ID").add_requirement(flaw_id);
+        }
+        let atom = self.get_atom(atm).expect("Atom should exist");
+        if !self.assert_temporal_constraints(&atom) {
+            // The atom cannot be part of the plan, hence neither can the resolver introducing it (nor the problem, at the root)
+            warn!("Atom {} violates its temporal constraints", atm);
+            self.learn(vec![!rho]);
+        } else if !self.assert_capacity_constraints(&atom) {
+            warn!("Atom {} exceeds the capacity of its resource", atm);
+            self.learn(vec![!rho]);
         }
         atm
     }
@@ -1026,11 +2321,56 @@ impl ToJson for SolverState {
         if let Some(current_resolver) = self.c_res.borrow().as_ref() {
             slv["current_resolver"] = json!(current_resolver.0);
         }
+        slv["statistics"] = self.statistics.borrow().to_json();
         slv
     }
 }
 
-fn bool_lit(var: &Slot) -> Lit {
+enum Conflict {
+    Ac(Vec<ac3rm::ConstraintId>),
+    Lin(Vec<linarith::GuardId>),
+}
+
+enum AcReif {
+    Equality(ac3rm::VarId, ac3rm::VarId),
+    Set(ac3rm::VarId, i32),
+}
+
+impl AcReif {
+    fn constraints(&self) -> (ac3rm::Constraint, ac3rm::Constraint) {
+        match *self {
+            AcReif::Equality(left, right) => (ac3rm::Constraint::Equality(left, right), ac3rm::Constraint::Inequality(left, right)),
+            AcReif::Set(var, val) => (ac3rm::Constraint::Set(var, val), ac3rm::Constraint::Forbid(var, val)),
+        }
+    }
+
+    fn entailment(&self, ac: &ac3rm::Engine) -> Option<bool> {
+        match *self {
+            AcReif::Equality(left, right) => {
+                let (left, right) = (ac.val(left), ac.val(right));
+                if left.len() == 1 && left == right {
+                    Some(true)
+                } else if !left.iter().any(|val| right.contains(val)) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            AcReif::Set(var, val) => {
+                let vals = ac.val(var);
+                if vals == [val] {
+                    Some(true)
+                } else if !vals.contains(&val) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn bool_lit(var: &Slot) -> Lit {
     if let Slot::Primitive(var) = var {
         var.clone().as_any().downcast_ref::<BoolVar>().expect("Expected BoolVar").lit
     } else {
@@ -1038,7 +2378,44 @@ fn bool_lit(var: &Slot) -> Lit {
     }
 }
 
-fn numeric_lin(var: &Slot) -> Lin {
+fn is_subpredicate_of(pred: &Rc<Predicate>, name: &str) -> bool {
+    if pred.name() == name {
+        return true;
+    }
+    for super_pred in pred.parents() {
+        if is_subpredicate_of(&get_predicate_by_path(pred.as_ref(), super_pred).expect("Predicate should exist"), name) {
+            return true;
+        }
+    }
+    false
+}
+
+fn as_node(lin: &Lin) -> Option<(Option<linarith::VarId>, Rational)> {
+    match lin.vars.iter().collect::<Vec<_>>().as_slice() {
+        [] => Some((None, lin.known_term)),
+        [(var, coeff)] if **coeff == Rational::from(1) => Some((Some(**var), lin.known_term)),
+        _ => None,
+    }
+}
+
+fn as_difference(lin: &Lin) -> Option<(Option<linarith::VarId>, Option<linarith::VarId>, Rational)> {
+    let (mut to, mut from) = (None, None);
+    for (var, coeff) in lin.vars.iter() {
+        if *coeff == Rational::from(1) && to.is_none() {
+            to = Some(*var);
+        } else if *coeff == Rational::from(-1) && from.is_none() {
+            from = Some(*var);
+        } else {
+            return None;
+        }
+    }
+    if to.is_none() && from.is_none() {
+        return None; // Constant expressions are left to the linear solver
+    }
+    Some((to, from, lin.known_term))
+}
+
+pub(crate) fn numeric_lin(var: &Slot) -> Lin {
     if let Slot::Primitive(var) = var {
         var.clone().as_any().downcast_ref::<ArithVar>().expect("Expected ArithVar").lin.clone()
     } else {
@@ -0,0 +1,40 @@
+use crate::{ToJson, heuristics::FlawKind};
+use serde_json::{Value, json};
+use std::{collections::HashMap, time::Duration};
+
+/// Counters describing the work done by the last solve, along with the reads preceding it.
+#[derive(Clone, Debug, Default)]
+pub struct Statistics {
+    pub flaws: HashMap<FlawKind, usize>,
+    pub resolvers: usize,
+    /// The resolvers applied while building the causal graph.
+    pub applied_resolvers: usize,
+    /// The resolvers chosen during the search.
+    pub decisions: usize,
+    pub graph_iterations: usize,
+    pub propagations: usize,
+    pub conflicts: usize,
+    /// The no-goods learnt from the conflicts and added to the SAT database.
+    pub learnts: usize,
+    pub cost_updates: usize,
+    pub graph_time: Duration,
+    pub search_time: Duration,
+}
+
+impl ToJson for Statistics {
+    fn to_json(&self) -> Value {
+        json!({
+            "flaws": self.flaws.iter().map(|(kind, count)| (kind.to_string(), json!(count))).collect::<serde_json::Map<_, _>>(),
+            "resolvers": self.resolvers,
+            "applied_resolvers": self.applied_resolvers,
+            "decisions": self.decisions,
+            "graph_iterations": self.graph_iterations,
+            "propagations": self.propagations,
+            "conflicts": self.conflicts,
+            "learnts": self.learnts,
+            "cost_updates": self.cost_updates,
+            "graph_time_ms": self.graph_time.as_millis() as u64,
+            "search_time_ms": self.search_time.as_millis() as u64,
+        })
+    }
+}
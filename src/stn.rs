@@ -0,0 +1,535 @@
+use linarith::{GuardId, Rational, VarId};
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, VecDeque},
+    ops::{Add, Neg, Sub},
+};
+
+/// A rational plus an integer multiple of an infinitesimal, for strict constraints.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Dist {
+    pub(crate) val: Rational,
+    pub(crate) eps: i32,
+}
+
+impl Dist {
+    fn zero() -> Self {
+        Self { val: Rational::from(0), eps: 0 }
+    }
+
+    fn infinity() -> Self {
+        Self { val: Rational::POSITIVE_INFINITY, eps: 0 }
+    }
+
+    pub(crate) fn new(val: Rational, strict: bool) -> Self {
+        Self { val, eps: if strict { -1 } else { 0 } }
+    }
+
+    fn is_infinite(&self) -> bool {
+        self.val.is_infinite()
+    }
+}
+
+impl Ord for Dist {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.val.cmp(&other.val).then(self.eps.cmp(&other.eps))
+    }
+}
+
+impl PartialOrd for Dist {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Dist {
+    type Output = Dist;
+
+    fn add(self, rhs: Dist) -> Dist {
+        Dist { val: self.val + rhs.val, eps: self.eps + rhs.eps }
+    }
+}
+
+impl Sub for Dist {
+    type Output = Dist;
+
+    fn sub(self, rhs: Dist) -> Dist {
+        Dist { val: self.val - rhs.val, eps: self.eps - rhs.eps }
+    }
+}
+
+impl Neg for Dist {
+    type Output = Dist;
+
+    fn neg(self) -> Dist {
+        Dist { val: -self.val, eps: -self.eps }
+    }
+}
+
+const ZERO: usize = 0;
+
+struct Edge {
+    from: usize,
+    to: usize,
+    weight: Dist,
+    guard: Option<GuardId>,
+}
+
+enum Change {
+    Potential(usize, Dist),
+    FromZero(usize, Dist),
+    ToZero(usize, Dist),
+    Enable(usize),
+}
+
+pub(crate) type Difference = (Option<VarId>, Option<VarId>, Dist, Option<GuardId>);
+
+/// A variable is owned by the network until it appears in a non-difference constraint, which releases it to the linear solver.
+pub(crate) struct Stn {
+    nodes: HashMap<VarId, usize>,
+    vars: Vec<Option<VarId>>,
+    released: Vec<bool>,
+    edges: Vec<Edge>,
+    enabled: Vec<bool>,
+    out_edges: Vec<Vec<usize>>,
+    in_edges: Vec<Vec<usize>>,
+    guarded: HashMap<GuardId, Vec<usize>>,
+    potentials: Vec<Dist>,
+    from_zero: Vec<Dist>,
+    to_zero: Vec<Dist>,
+    trail: Vec<Change>,
+    layers: Vec<usize>,
+}
+
+impl Default for Stn {
+    fn default() -> Self {
+        Self {
+            nodes: HashMap::new(),
+            vars: vec![None],
+            released: vec![false],
+            edges: Vec::new(),
+            enabled: Vec::new(),
+            out_edges: vec![Vec::new()],
+            in_edges: vec![Vec::new()],
+            guarded: HashMap::new(),
+            potentials: vec![Dist::zero()],
+            from_zero: vec![Dist::zero()],
+            to_zero: vec![Dist::zero()],
+            trail: Vec::new(),
+            layers: Vec::new(),
+        }
+    }
+}
+
+impl Stn {
+    pub(crate) fn owns(&self, var: VarId) -> bool {
+        self.nodes.get(&var).is_some_and(|node| !self.released[*node])
+    }
+
+    pub(crate) fn is_released(&self, var: VarId) -> bool {
+        self.nodes.get(&var).is_some_and(|node| self.released[*node])
+    }
+
+    fn node(&mut self, var: Option<VarId>) -> usize {
+        let Some(var) = var else {
+            return ZERO;
+        };
+        if let Some(node) = self.nodes.get(&var) {
+            return *node;
+        }
+        let node = self.vars.len();
+        self.nodes.insert(var, node);
+        self.vars.push(Some(var));
+        self.released.push(false);
+        self.out_edges.push(Vec::new());
+        self.in_edges.push(Vec::new());
+        self.potentials.push(Dist::zero());
+        self.from_zero.push(Dist::infinity());
+        self.to_zero.push(Dist::infinity());
+        node
+    }
+
+    pub(crate) fn new_constraint(&mut self, to: Option<VarId>, from: Option<VarId>, weight: Dist, guard: Option<GuardId>) -> Result<(), Vec<GuardId>> {
+        let (from, to) = (self.node(from), self.node(to));
+        let edge = self.edges.len();
+        self.edges.push(Edge { from, to, weight, guard });
+        self.enabled.push(false);
+        match guard {
+            Some(guard) => {
+                self.guarded.entry(guard).or_default().push(edge);
+                Ok(())
+            }
+            None => self.enable(edge),
+        }
+    }
+
+    /// Leaves the network unchanged on failure.
+    pub(crate) fn assert(&mut self, guard: GuardId) -> Result<(), Vec<GuardId>> {
+        let mark = self.trail.len();
+        for edge in self.guarded.get(&guard).cloned().unwrap_or_default() {
+            if let Err(conflict) = self.enable(edge) {
+                self.undo(mark);
+                return Err(conflict);
+            }
+        }
+        Ok(())
+    }
+
+    fn enable(&mut self, edge: usize) -> Result<(), Vec<GuardId>> {
+        if self.enabled[edge] {
+            return Ok(());
+        }
+        let (from, to, weight) = (self.edges[edge].from, self.edges[edge].to, self.edges[edge].weight);
+        if from == to && weight < Dist::zero() {
+            return Err(self.edges[edge].guard.into_iter().collect());
+        }
+
+        // Repairs the potential function, a negative cycle being closed whenever the repair reaches back the source of the new edge
+        let mark = self.trail.len();
+        let mut preds: HashMap<usize, usize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        if self.potentials[from] + weight < self.potentials[to] {
+            self.set_potential(to, self.potentials[from] + weight);
+            preds.insert(to, edge);
+            queue.push_back(to);
+        }
+        while let Some(node) = queue.pop_front() {
+            for out in self.out_edges[node].clone() {
+                let next = self.edges[out].to;
+                let dist = self.potentials[node] + self.edges[out].weight;
+                if dist >= self.potentials[next] {
+                    continue;
+                }
+                preds.insert(next, out);
+                if next == from {
+                    let conflict = self.explain(&preds, from);
+                    self.undo(mark);
+                    return Err(conflict);
+                }
+                self.set_potential(next, dist);
+                queue.push_back(next);
+            }
+        }
+
+        self.enabled[edge] = true;
+        self.out_edges[from].push(edge);
+        self.in_edges[to].push(edge);
+        self.trail.push(Change::Enable(edge));
+
+        // Tightens the distances from the zero node, i.e., the upper bounds
+        if !self.from_zero[from].is_infinite() && self.from_zero[from] + weight < self.from_zero[to] {
+            self.set_from_zero(to, self.from_zero[from] + weight);
+            let mut queue = VecDeque::from([to]);
+            while let Some(node) = queue.pop_front() {
+                for out in self.out_edges[node].clone() {
+                    let next = self.edges[out].to;
+                    let dist = self.from_zero[node] + self.edges[out].weight;
+                    if dist < self.from_zero[next] {
+                        self.set_from_zero(next, dist);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        // Tightens the distances to the zero node, i.e., the opposite of the lower bounds
+        if !self.to_zero[to].is_infinite() && self.to_zero[to] + weight < self.to_zero[from] {
+            self.set_to_zero(from, self.to_zero[to] + weight);
+            let mut queue = VecDeque::from([from]);
+            while let Some(node) = queue.pop_front() {
+                for inc in self.in_edges[node].clone() {
+                    let prev = self.edges[inc].from;
+                    let dist = self.to_zero[node] + self.edges[inc].weight;
+                    if dist < self.to_zero[prev] {
+                        self.set_to_zero(prev, dist);
+                        queue.push_back(prev);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn explain(&self, preds: &HashMap<usize, usize>, closing: usize) -> Vec<GuardId> {
+        let mut guards = Vec::new();
+        let mut node = closing;
+        loop {
+            let edge = &self.edges[preds[&node]];
+            if let Some(guard) = edge.guard
+                && !guards.contains(&guard)
+            {
+                guards.push(guard);
+            }
+            node = edge.from;
+            if node == closing {
+                return guards;
+            }
+        }
+    }
+
+    /// Releases the variable along with all those connected to it. Releases are permanent, since the linear solver keeps the constraints it receives.
+    pub(crate) fn release(&mut self, var: VarId) -> Vec<Difference> {
+        let mut constraints = Vec::new();
+        let node = self.node(Some(var));
+        let mut queue = VecDeque::from([node]);
+        while let Some(node) = queue.pop_front() {
+            if node == ZERO || self.released[node] {
+                continue;
+            }
+            self.released[node] = true;
+            for edge in self.edges.iter().filter(|edge| edge.from == node || edge.to == node) {
+                let other = if edge.from == node { edge.to } else { edge.from };
+                // The constraints between two released variables have already been returned
+                if other == ZERO || !self.released[other] {
+                    constraints.push((self.vars[edge.to], self.vars[edge.from], edge.weight, edge.guard));
+                    queue.push_back(other);
+                }
+            }
+        }
+        constraints
+    }
+
+    pub(crate) fn ub(&self, var: VarId) -> Rational {
+        self.nodes.get(&var).map_or(Rational::POSITIVE_INFINITY, |node| self.from_zero[*node].val)
+    }
+
+    pub(crate) fn lb(&self, var: VarId) -> Rational {
+        self.nodes.get(&var).map_or(Rational::NEGATIVE_INFINITY, |node| -self.to_zero[*node].val)
+    }
+
+    pub(crate) fn distance(&self, to: Option<VarId>, from: Option<VarId>) -> Dist {
+        let node = |var: Option<VarId>| var.map_or(Some(ZERO), |var| self.nodes.get(&var).copied());
+        let (Some(from), Some(to)) = (node(from), node(to)) else {
+            return Dist::infinity();
+        };
+        let mut dists: HashMap<usize, Dist> = HashMap::from([(from, Dist::zero())]);
+        let mut queue = BinaryHeap::from([Reverse((Dist::zero(), from))]);
+        while let Some(Reverse((dist, node))) = queue.pop() {
+            if dists.get(&node).is_some_and(|best| *best < dist) {
+                continue;
+            }
+            if node == to {
+                // Restores the original weights from the reduced ones
+                return dist - self.potentials[from] + self.potentials[to];
+            }
+            for out in self.out_edges[node].iter() {
+                let edge = &self.edges[*out];
+                let next = dist + edge.weight + self.potentials[node] - self.potentials[edge.to];
+                if dists.get(&edge.to).is_none_or(|best| next < *best) {
+                    dists.insert(edge.to, next);
+                    queue.push(Reverse((next, edge.to)));
+                }
+            }
+        }
+        Dist::infinity()
+    }
+
+    pub(crate) fn val(&self, var: VarId) -> Rational {
+        let Some(node) = self.nodes.get(&var) else {
+            return Rational::from(0);
+        };
+        let val = self.potentials[*node] - self.potentials[ZERO];
+        val.val + Rational::from(val.eps) * self.delta()
+    }
+
+    fn delta(&self) -> Rational {
+        let mut delta = Rational::from(1);
+        for (edge, enabled) in self.edges.iter().zip(self.enabled.iter()) {
+            if !enabled {
+                continue;
+            }
+            let diff = self.potentials[edge.to] - self.potentials[edge.from];
+            if diff.val < edge.weight.val && diff.eps > edge.weight.eps {
+                let bound = (edge.weight.val - diff.val) / Rational::from(diff.eps - edge.weight.eps);
+                if bound < delta {
+                    delta = bound;
+                }
+            }
+        }
+        delta
+    }
+
+    pub(crate) fn push(&mut self) {
+        self.layers.push(self.trail.len());
+    }
+
+    pub(crate) fn pop(&mut self) {
+        let mark = self.layers.pop().expect("No layer to pop");
+        self.undo(mark);
+    }
+
+    fn set_potential(&mut self, node: usize, dist: Dist) {
+        self.trail.push(Change::Potential(node, self.potentials[node]));
+        self.potentials[node] = dist;
+    }
+
+    fn set_from_zero(&mut self, node: usize, dist: Dist) {
+        self.trail.push(Change::FromZero(node, self.from_zero[node]));
+        self.from_zero[node] = dist;
+    }
+
+    fn set_to_zero(&mut self, node: usize, dist: Dist) {
+        self.trail.push(Change::ToZero(node, self.to_zero[node]));
+        self.to_zero[node] = dist;
+    }
+
+    fn undo(&mut self, mark: usize) {
+        while self.trail.len() > mark {
+            match self.trail.pop().expect("Trail should not be empty") {
+                Change::Potential(node, dist) => self.potentials[node] = dist,
+                Change::FromZero(node, dist) => self.from_zero[node] = dist,
+                Change::ToZero(node, dist) => self.to_zero[node] = dist,
+                Change::Enable(edge) => {
+                    self.enabled[edge] = false;
+                    self.out_edges[self.edges[edge].from].pop();
+                    self.in_edges[self.edges[edge].to].pop();
+                }
+            }
+        }
+    }
+}
+
+/// The environment chooses `contingent - activation` within `[lb, ub]`.
+pub(crate) struct ContingentLink {
+    pub(crate) activation: Option<VarId>,
+    pub(crate) contingent: VarId,
+    pub(crate) lb: Rational,
+    pub(crate) ub: Rational,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Label {
+    Ordinary,
+    Lower(usize),
+    Upper(usize),
+}
+
+#[derive(Clone, Copy)]
+struct LabeledEdge {
+    from: usize,
+    weight: Dist,
+    label: Label,
+    guard: Option<GuardId>,
+}
+
+/// Morris' `O(n^3)` dynamic controllability check.
+struct Controllability {
+    in_edges: Vec<Vec<LabeledEdge>>,
+    on_stack: Vec<bool>,
+    done: Vec<bool>,
+    guards: Vec<GuardId>,
+    links: Vec<usize>,
+}
+
+impl Controllability {
+    fn is_negative(&self, node: usize) -> bool {
+        self.in_edges[node].iter().any(|edge| edge.weight < Dist::zero())
+    }
+
+    fn involve(&mut self, edge: &LabeledEdge) {
+        if let Some(guard) = edge.guard
+            && !self.guards.contains(&guard)
+        {
+            self.guards.push(guard);
+        }
+        if let Label::Lower(link) | Label::Upper(link) = edge.label
+            && !self.links.contains(&link)
+        {
+            self.links.push(link);
+        }
+    }
+
+    fn backprop(&mut self, source: usize) -> bool {
+        if self.on_stack[source] {
+            return false;
+        }
+        if self.done[source] {
+            return true;
+        }
+        self.on_stack[source] = true;
+
+        // The distances to the source, along with the contingent link of the upper-case edge the paths start with, if any
+        let mut dists: HashMap<usize, (Dist, Option<usize>)> = HashMap::new();
+        let mut queue = BinaryHeap::new();
+        for edge in self.in_edges[source].clone() {
+            if edge.weight >= Dist::zero() {
+                continue;
+            }
+            self.involve(&edge);
+            let upper = if let Label::Upper(link) = edge.label { Some(link) } else { None };
+            if dists.get(&edge.from).is_none_or(|(best, _)| edge.weight < *best) {
+                dists.insert(edge.from, (edge.weight, upper));
+                queue.push(Reverse((edge.weight, edge.from)));
+            }
+        }
+        while let Some(Reverse((dist, node))) = queue.pop() {
+            let (best, upper) = dists[&node];
+            if best < dist {
+                continue;
+            }
+            if dist >= Dist::zero() {
+                if node != source {
+                    self.in_edges[source].push(LabeledEdge { from: node, weight: dist, label: Label::Ordinary, guard: None });
+                }
+                continue;
+            }
+            if self.is_negative(node) && !self.backprop(node) {
+                return false;
+            }
+            for i in 0..self.in_edges[node].len() {
+                let edge = self.in_edges[node][i];
+                if edge.weight < Dist::zero() {
+                    continue;
+                }
+                if let (Label::Lower(link), Some(upper)) = (edge.label, upper)
+                    && link == upper
+                {
+                    continue; // A lower-case edge cannot follow the upper-case edge of the same contingent link
+                }
+                self.involve(&edge);
+                let next = dist + edge.weight;
+                if dists.get(&edge.from).is_none_or(|(best, _)| next < *best) {
+                    dists.insert(edge.from, (next, upper));
+                    queue.push(Reverse((next, edge.from)));
+                }
+            }
+        }
+
+        self.on_stack[source] = false;
+        self.done[source] = true;
+        true
+    }
+}
+
+impl Stn {
+    pub(crate) fn check_controllability(&self, links: &[ContingentLink]) -> Result<(), (Vec<GuardId>, Vec<usize>)> {
+        let mut in_edges: Vec<Vec<LabeledEdge>> = vec![Vec::new(); self.vars.len()];
+        for (edge, enabled) in self.edges.iter().zip(self.enabled.iter()) {
+            if *enabled {
+                in_edges[edge.to].push(LabeledEdge { from: edge.from, weight: edge.weight, label: Label::Ordinary, guard: edge.guard });
+            }
+        }
+        for (i, link) in links.iter().enumerate() {
+            let activation = link.activation.map_or(Some(ZERO), |var| self.nodes.get(&var).copied());
+            let (Some(activation), Some(contingent)) = (activation, self.nodes.get(&link.contingent).copied()) else {
+                continue; // Unconstrained links cannot affect the controllability
+            };
+            in_edges[contingent].push(LabeledEdge { from: activation, weight: Dist::new(link.lb, false), label: Label::Lower(i), guard: None });
+            in_edges[activation].push(LabeledEdge { from: contingent, weight: Dist::new(-link.ub, false), label: Label::Upper(i), guard: None });
+        }
+
+        let nodes = in_edges.len();
+        let mut check = Controllability {
+            in_edges,
+            on_stack: vec![false; nodes],
+            done: vec![false; nodes],
+            guards: Vec::new(),
+            links: Vec::new(),
+        };
+        for node in 0..nodes {
+            if check.is_negative(node) && !check.backprop(node) {
+                return Err((check.guards, check.links));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    flaws::{FlawId, peak_flaw::PeakFlaw},
+    heuristics::FlawKind,
+    solver_state::{SolverState, numeric_lin},
+    timelines::{Flawed, Timeline, atom_bounds, is_subclass_of},
+};
+use linarith::Rational;
+use riddle::{
+    core::Core,
+    env::{Atom, AtomId, ObjectId, Slot},
+    scope::Class,
+};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+use watchsat::{neg, pos};
+
+pub(crate) struct ConsumableResource {
+    slv: Weak<SolverState>,
+    objects: RefCell<Vec<ObjectId>>,
+    flawed: Flawed<Vec<usize>>,
+}
+
+impl ConsumableResource {
+    pub(crate) fn new(slv: Weak<SolverState>) -> Self {
+        Self { slv, objects: RefCell::new(Vec::new()), flawed: Flawed::new() }
+    }
+}
+
+impl Timeline for ConsumableResource {
+    fn handles(&self, class: &Rc<dyn Class>) -> bool {
+        is_subclass_of(class, "ConsumableResource")
+    }
+
+    fn add_object(&self, id: ObjectId) {
+        self.objects.borrow_mut().push(id);
+    }
+
+    fn push(&self) {
+        self.flawed.push();
+    }
+
+    fn pop(&self) {
+        self.flawed.pop();
+    }
+
+    fn check(&self) -> Vec<FlawId> {
+        let solver = self.slv.upgrade().expect("Solver has been dropped");
+        let mut flaws = Vec::new();
+        for object in self.objects.borrow().iter() {
+            let (initial, min, max) = amounts(&solver, *object);
+            let changes = changes(&solver, *object);
+            let mut level = initial;
+            for (i, (_, time, amount)) in changes.iter().enumerate() {
+                level = level + *amount;
+                if i + 1 < changes.len() && changes[i + 1].1 == *time {
+                    continue; // The level is checked once all the simultaneous changes have been applied
+                }
+                let (before, after) = changes.split_at(i + 1);
+                // An underflow is fixed by anticipating some later production, an overflow by anticipating some later consumption
+                let orderings = if level < min {
+                    after.iter().filter(|(_, _, amount)| *amount > Rational::from(0)).flat_map(|(p, _, _)| before.iter().filter(|(_, _, amount)| *amount < Rational::from(0)).map(move |(c, _, _)| (*p, *c))).collect::<Vec<_>>()
+                } else if level > max {
+                    after.iter().filter(|(_, _, amount)| *amount < Rational::from(0)).flat_map(|(c, _, _)| before.iter().filter(|(_, _, amount)| *amount > Rational::from(0)).map(move |(p, _, _)| (*c, *p))).collect::<Vec<_>>()
+                } else {
+                    continue;
+                };
+                let mut atoms = before.iter().map(|(atom, _, _)| *atom).collect::<Vec<_>>();
+                atoms.sort_by_key(|atom| **atom);
+                let mut key = vec![if level < min { 0 } else { 1 }];
+                key.extend(atoms.iter().map(|atom| **atom));
+                key.extend(orderings.iter().flat_map(|(a, b)| [**a, **b]));
+                if !self.flawed.insert(key) {
+                    continue;
+                }
+                let phi = solver.sat.borrow_mut().add_var();
+                let flaw_id = FlawId(solver.get_flaws_len());
+                solver.add_flaw(PeakFlaw::new(self.slv.clone(), flaw_id, phi, FlawKind::ConsumableResource, atoms.clone(), orderings));
+                // The flaw is active as long as all the atoms which lead to the violation are
+                let mut clause = atoms.iter().map(|atom| neg(solver.get_sigma(*atom))).collect::<Vec<_>>();
+                clause.push(pos(phi));
+                solver.sat.borrow_mut().add_clause(clause).expect("Failed to add clause for consumable resource flaw");
+                flaws.push(flaw_id);
+                break; // Later violations might be fixed by the same resolvers
+            }
+        }
+        flaws
+    }
+
+    // The flaws are detected on the current values, which might have changed since the last check
+    fn is_consistent(&self) -> bool {
+        let solver = self.slv.upgrade().expect("Solver has been dropped");
+        self.objects.borrow().iter().all(|object| {
+            let (initial, min, max) = amounts(&solver, *object);
+            let changes = changes(&solver, *object);
+            let mut level = initial;
+            changes.iter().enumerate().all(|(i, (_, time, amount))| {
+                level = level + *amount;
+                (i + 1 < changes.len() && changes[i + 1].1 == *time) || (min <= level && level <= max)
+            })
+        })
+    }
+}
+
+/// Returns the current initial, minimum and maximum amounts of a consumable resource.
+fn amounts(solver: &SolverState, object: ObjectId) -> (Rational, Rational, Rational) {
+    let obj = solver.get_object(object).expect("Consumable resource should exist");
+    let field = |name: &str| solver.arith_val(&numeric_lin(&obj.get(name).expect("Consumable resource should have its bounds")));
+    (field("initial_amount"), field("min_amount"), field("max_amount"))
+}
+
+/// Returns the changes of the level of a consumable resource, sorted by the current values of the time points at which they take effect.
+fn changes(solver: &SolverState, object: ObjectId) -> Vec<(AtomId, Rational, Rational)> {
+    // Productions take effect at their end, consumptions at their start
+    let mut changes = solver
+        .active_atoms_on(object)
+        .into_iter()
+        .filter_map(|atom| {
+            let time = solver.arith_val(&numeric_lin(&effect_time(&atom)?));
+            let amount = solver.arith_val(&numeric_lin(&atom.get("amount")?));
+            match atom.predicate().name() {
+                "Produce" => Some((atom.id(), time, amount)),
+                _ => Some((atom.id(), time, -amount)),
+            }
+        })
+        .collect::<Vec<_>>();
+    changes.sort_by_key(|(_, time, _)| *time);
+    changes
+}
+
+/// Returns the time point at which an atom changes the level of a consumable resource, i.e., the end of a production or the start of a consumption.
+pub(crate) fn effect_time(atom: &Atom) -> Option<Slot> {
+    let (start, end) = atom_bounds(atom)?;
+    match atom.predicate().name() {
+        "Produce" => Some(end),
+        "Consume" => Some(start),
+        _ => None,
+    }
+}
@@ -0,0 +1,79 @@
+use crate::flaws::FlawId;
+use riddle::{
+    env::{Atom, ObjectId, Slot},
+    scope::Class,
+};
+use std::{cell::RefCell, collections::HashSet, hash::Hash, rc::Rc};
+
+pub(crate) mod consumable_resource;
+pub(crate) mod propositional_state;
+pub(crate) mod reusable_resource;
+pub(crate) mod state_variable;
+
+/// A predeclared class whose instances check the atoms scoped to them, raising flaws on the inconsistencies of the current plan.
+pub(crate) trait Timeline {
+    /// Whether the instances of the given class are handled by this timeline.
+    fn handles(&self, class: &Rc<dyn Class>) -> bool;
+    fn add_object(&self, id: ObjectId);
+    /// Raises a flaw for each inconsistency which has not been flawed yet, returning the ids of the new flaws.
+    fn check(&self) -> Vec<FlawId>;
+    /// Opens a new decision level.
+    fn push(&self);
+    /// Forgets the inconsistencies flawed since the last decision level was opened, so that they are flawed again if they reappear.
+    fn pop(&self);
+    /// Whether the current plan satisfies the constraints of this timeline, which holds once no more flaws are raised, unless the flaws are detected on values that might change.
+    fn is_consistent(&self) -> bool {
+        true
+    }
+}
+
+/// The inconsistencies flawed so far, trailed by decision level.
+pub(crate) struct Flawed<K> {
+    keys: RefCell<HashSet<K>>,
+    trail: RefCell<Vec<K>>,
+    layers: RefCell<Vec<usize>>,
+}
+
+impl<K: Clone + Eq + Hash> Flawed<K> {
+    pub(crate) fn new() -> Self {
+        Self {
+            keys: RefCell::new(HashSet::new()),
+            trail: RefCell::new(Vec::new()),
+            layers: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn contains(&self, key: &K) -> bool {
+        self.keys.borrow().contains(key)
+    }
+
+    /// Records the given inconsistency, returning whether it was not flawed yet.
+    pub(crate) fn insert(&self, key: K) -> bool {
+        if !self.keys.borrow_mut().insert(key.clone()) {
+            return false;
+        }
+        self.trail.borrow_mut().push(key);
+        true
+    }
+
+    pub(crate) fn push(&self) {
+        self.layers.borrow_mut().push(self.trail.borrow().len());
+    }
+
+    pub(crate) fn pop(&self) {
+        let len = self.layers.borrow_mut().pop().expect("No decision level to pop");
+        let mut keys = self.keys.borrow_mut();
+        for key in self.trail.borrow_mut().drain(len..) {
+            keys.remove(&key);
+        }
+    }
+}
+
+pub(crate) fn is_subclass_of(class: &Rc<dyn Class>, name: &str) -> bool {
+    class.name() == name || class.parents().iter().any(|parent| is_subclass_of(parent, name))
+}
+
+/// Returns the start and the end of a temporal atom, i.e., one having either the `start` and `end` or the `at` arguments.
+pub(crate) fn atom_bounds(atom: &Atom) -> Option<(Slot, Slot)> {
+    if let (Some(start), Some(end)) = (atom.get("start"), atom.get("end")) { Some((start, end)) } else { atom.get("at").map(|at| (at.clone(), at)) }
+}
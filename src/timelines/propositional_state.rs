@@ -0,0 +1,98 @@
+use crate::{
+    flaws::{
+        FlawId,
+        propositional_state_flaw::{PropositionalStateFlaw, fluent_equalities},
+    },
+    solver_state::{SolverState, bool_lit, numeric_lin},
+    timelines::{Flawed, Timeline, atom_bounds, is_subclass_of},
+};
+use riddle::{
+    env::{Atom, ObjectId},
+    scope::Class,
+};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+use watchsat::{LBool, neg, pos};
+
+pub(crate) struct PropositionalState {
+    slv: Weak<SolverState>,
+    objects: RefCell<Vec<ObjectId>>,
+    flawed: Flawed<(usize, usize)>,
+}
+
+impl PropositionalState {
+    pub(crate) fn new(slv: Weak<SolverState>) -> Self {
+        Self { slv, objects: RefCell::new(Vec::new()), flawed: Flawed::new() }
+    }
+}
+
+/// Returns the polarity of a fluent, if known. Atoms without a `polarity` argument are positive.
+fn polarity(solver: &SolverState, atom: &Atom) -> Option<bool> {
+    match atom.get("polarity") {
+        Some(polarity) => match solver.sat.borrow().lit_value(&bool_lit(&polarity)) {
+            LBool::True => Some(true),
+            LBool::False => Some(false),
+            LBool::Undef => None,
+        },
+        None => Some(true),
+    }
+}
+
+impl Timeline for PropositionalState {
+    fn handles(&self, class: &Rc<dyn Class>) -> bool {
+        is_subclass_of(class, "PropositionalState")
+    }
+
+    fn add_object(&self, id: ObjectId) {
+        self.objects.borrow_mut().push(id);
+    }
+
+    fn push(&self) {
+        self.flawed.push();
+    }
+
+    fn pop(&self) {
+        self.flawed.pop();
+    }
+
+    fn check(&self) -> Vec<FlawId> {
+        let solver = self.slv.upgrade().expect("Solver has been dropped");
+        let mut flaws = Vec::new();
+        for object in self.objects.borrow().iter() {
+            // Atoms whose polarity is still unknown are not checked
+            let atoms = solver
+                .active_atoms_on(*object)
+                .into_iter()
+                .filter_map(|atom| match (atom_bounds(&atom), polarity(&solver, &atom)) {
+                    (Some((start, end)), Some(polarity)) => Some((atom, numeric_lin(&start), numeric_lin(&end), polarity)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+            for (i, (a, a_start, a_end, a_polarity)) in atoms.iter().enumerate() {
+                for (b, b_start, b_end, b_polarity) in atoms.iter().skip(i + 1) {
+                    let same_predicate = a.predicate().name() == b.predicate().name();
+                    let conflicting = match (a_polarity, b_polarity) {
+                        // Two positive atoms of a fluent without arguments are always the same fluent
+                        (true, true) => !same_predicate || !fluent_equalities(&solver, a, b).is_empty(),
+                        (false, false) => false,
+                        _ => same_predicate,
+                    };
+                    let pair = (*a.id(), *b.id());
+                    if !conflicting || self.flawed.contains(&pair) || !solver.may_overlap(a_start, a_end, b_start, b_end) {
+                        continue;
+                    }
+                    self.flawed.insert(pair);
+                    let phi = solver.sat.borrow_mut().add_var();
+                    let flaw_id = FlawId(solver.get_flaws_len());
+                    solver.add_flaw(PropositionalStateFlaw::new(self.slv.clone(), flaw_id, phi, a.id(), b.id(), a_polarity == b_polarity));
+                    // The flaw is active as long as both atoms are
+                    solver.sat.borrow_mut().add_clause(vec![neg(solver.get_sigma(a.id())), neg(solver.get_sigma(b.id())), pos(phi)]).expect("Failed to add clause for propositional state flaw");
+                    flaws.push(flaw_id);
+                }
+            }
+        }
+        flaws
+    }
+}
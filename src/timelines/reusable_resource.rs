@@ -0,0 +1,87 @@
+use crate::{
+    flaws::{FlawId, peak_flaw::PeakFlaw},
+    heuristics::FlawKind,
+    solver_state::{SolverState, numeric_lin},
+    timelines::{Flawed, Timeline, atom_bounds, is_subclass_of},
+};
+use linarith::Rational;
+use riddle::{core::Core, env::ObjectId, scope::Class};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+use watchsat::{neg, pos};
+
+pub(crate) struct ReusableResource {
+    slv: Weak<SolverState>,
+    objects: RefCell<Vec<ObjectId>>,
+    flawed: Flawed<Vec<usize>>,
+}
+
+impl ReusableResource {
+    pub(crate) fn new(slv: Weak<SolverState>) -> Self {
+        Self { slv, objects: RefCell::new(Vec::new()), flawed: Flawed::new() }
+    }
+}
+
+impl Timeline for ReusableResource {
+    fn handles(&self, class: &Rc<dyn Class>) -> bool {
+        is_subclass_of(class, "ReusableResource")
+    }
+
+    fn add_object(&self, id: ObjectId) {
+        self.objects.borrow_mut().push(id);
+    }
+
+    fn push(&self) {
+        self.flawed.push();
+    }
+
+    fn pop(&self) {
+        self.flawed.pop();
+    }
+
+    fn check(&self) -> Vec<FlawId> {
+        let solver = self.slv.upgrade().expect("Solver has been dropped");
+        let mut flaws = Vec::new();
+        for object in self.objects.borrow().iter() {
+            let capacity = solver.get_object(*object).and_then(|obj| obj.get("capacity")).expect("Reusable resource should have a capacity");
+            let capacity = solver.arith_val(&numeric_lin(&capacity));
+            // The current values of the uses' start, end and amount
+            let uses = solver
+                .active_atoms_on(*object)
+                .into_iter()
+                .filter_map(|atom| {
+                    let (start, end) = atom_bounds(&atom)?;
+                    let amount = atom.get("amount")?;
+                    Some((atom.id(), solver.arith_val(&numeric_lin(&start)), solver.arith_val(&numeric_lin(&end)), solver.arith_val(&numeric_lin(&amount))))
+                })
+                .collect::<Vec<_>>();
+
+            // Each peak starts with some use, so it suffices to check the uses overlapping each start
+            for (_, t, _, _) in uses.iter() {
+                let peak = uses.iter().filter(|(_, start, end, _)| start <= t && t < end).collect::<Vec<_>>();
+                let usage = peak.iter().fold(Rational::from(0), |sum, (_, _, _, amount)| sum + *amount);
+                if usage <= capacity || peak.len() < 2 {
+                    continue;
+                }
+                let mut atoms = peak.iter().map(|(atom, _, _, _)| *atom).collect::<Vec<_>>();
+                atoms.sort_by_key(|atom| **atom);
+                let key = atoms.iter().map(|atom| **atom).collect::<Vec<_>>();
+                if !self.flawed.insert(key) {
+                    continue;
+                }
+                let orderings = atoms.iter().flat_map(|a| atoms.iter().filter(move |b| *b != a).map(move |b| (*a, *b))).collect::<Vec<_>>();
+                let phi = solver.sat.borrow_mut().add_var();
+                let flaw_id = FlawId(solver.get_flaws_len());
+                solver.add_flaw(PeakFlaw::new(self.slv.clone(), flaw_id, phi, FlawKind::ReusableResource, atoms.clone(), orderings));
+                // The flaw is active as long as all the atoms of the peak are
+                let mut clause = atoms.iter().map(|atom| neg(solver.get_sigma(*atom))).collect::<Vec<_>>();
+                clause.push(pos(phi));
+                solver.sat.borrow_mut().add_clause(clause).expect("Failed to add clause for reusable resource flaw");
+                flaws.push(flaw_id);
+            }
+        }
+        flaws
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    flaws::{FlawId, state_variable_flaw::StateVariableFlaw},
+    solver_state::{SolverState, numeric_lin},
+    timelines::{Flawed, Timeline, atom_bounds, is_subclass_of},
+};
+use riddle::{env::ObjectId, scope::Class};
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+use watchsat::{neg, pos};
+
+pub(crate) struct StateVariable {
+    slv: Weak<SolverState>,
+    objects: RefCell<Vec<ObjectId>>,
+    flawed: Flawed<(usize, usize)>,
+}
+
+impl StateVariable {
+    pub(crate) fn new(slv: Weak<SolverState>) -> Self {
+        Self { slv, objects: RefCell::new(Vec::new()), flawed: Flawed::new() }
+    }
+}
+
+impl Timeline for StateVariable {
+    fn handles(&self, class: &Rc<dyn Class>) -> bool {
+        is_subclass_of(class, "StateVariable")
+    }
+
+    fn add_object(&self, id: ObjectId) {
+        self.objects.borrow_mut().push(id);
+    }
+
+    fn push(&self) {
+        self.flawed.push();
+    }
+
+    fn pop(&self) {
+        self.flawed.pop();
+    }
+
+    fn check(&self) -> Vec<FlawId> {
+        let solver = self.slv.upgrade().expect("Solver has been dropped");
+        let mut flaws = Vec::new();
+        for object in self.objects.borrow().iter() {
+            let atoms = solver.active_atoms_on(*object).into_iter().filter_map(|atom| atom_bounds(&atom).map(|(start, end)| (atom, numeric_lin(&start), numeric_lin(&end)))).collect::<Vec<_>>();
+            for (i, (a, a_start, a_end)) in atoms.iter().enumerate() {
+                for (b, b_start, b_end) in atoms.iter().skip(i + 1) {
+                    let pair = (*a.id(), *b.id());
+                    if self.flawed.contains(&pair) || !solver.may_overlap(a_start, a_end, b_start, b_end) {
+                        continue;
+                    }
+                    self.flawed.insert(pair);
+                    let phi = solver.sat.borrow_mut().add_var();
+                    let flaw_id = FlawId(solver.get_flaws_len());
+                    solver.add_flaw(StateVariableFlaw::new(self.slv.clone(), flaw_id, phi, a.id(), b.id()));
+                    // The flaw is active as long as both atoms are
+                    solver.sat.borrow_mut().add_clause(vec![neg(solver.get_sigma(a.id())), neg(solver.get_sigma(b.id())), pos(phi)]).expect("Failed to add clause for state variable flaw");
+                    flaws.push(flaw_id);
+                }
+            }
+        }
+        flaws
+    }
+}
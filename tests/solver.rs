@@ -1,5 +1,16 @@
-use chronoxide::solver::Solver;
-use std::{fs::read_to_string, path::PathBuf};
+use chronoxide::{
+    heuristics::{CostHeuristic, FailFirst, Fifo, FlawId, FlawInfo, FlawKind, FlawSelector, MostExpensiveFlaw, PreferUnification, ValueOrdering},
+    solution::{AtomSolution, VarValue},
+    solver::{CancellationHandle, SolveLimits, Solver, SolverError},
+};
+use linarith::Rational;
+use std::{
+    cmp::Ordering,
+    fs::read_to_string,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use watchsat::LBool;
 
 macro_rules! test_chronoxide {
     ($name:ident, $($path:expr),+) => {
@@ -40,3 +51,524 @@ test_chronoxide!(test_core_03, "tests/examples/core/example_03.rddl");
 test_chronoxide!(test_core_04, "tests/examples/core/example_04.rddl");
 test_inconsistent!(test_core_05, "tests/examples/core/example_05.rddl");
 // test_inconsistent!(test_core_06, "tests/examples/core/example_06.rddl");
+test_chronoxide!(test_core_07, "tests/examples/core/example_07.rddl");
+test_inconsistent!(test_core_08, "tests/examples/core/example_08.rddl");
+test_chronoxide!(test_core_09, "tests/examples/core/example_09.rddl");
+test_chronoxide!(test_core_10, "tests/examples/core/example_10.rddl");
+test_inconsistent!(test_core_12, "tests/examples/core/example_12.rddl");
+test_inconsistent!(test_core_15, "tests/examples/core/example_15.rddl");
+test_chronoxide!(test_core_16, "tests/examples/core/example_16.rddl");
+test_inconsistent!(test_core_17, "tests/examples/core/example_17.rddl");
+test_inconsistent!(test_core_19, "tests/examples/core/example_19.rddl");
+test_chronoxide!(test_core_20, "tests/examples/core/example_20.rddl");
+test_inconsistent!(test_core_21, "tests/examples/core/example_21.rddl");
+test_chronoxide!(test_core_22, "tests/examples/core/example_22.rddl");
+test_inconsistent!(test_core_23, "tests/examples/core/example_23.rddl");
+test_inconsistent!(test_core_24, "tests/examples/core/example_24.rddl");
+test_chronoxide!(test_core_26, "tests/examples/core/example_26.rddl");
+test_inconsistent!(test_core_27, "tests/examples/core/example_27.rddl");
+test_chronoxide!(test_core_28, "tests/examples/core/example_28.rddl");
+test_inconsistent!(test_core_29, "tests/examples/core/example_29.rddl");
+test_chronoxide!(test_core_30, "tests/examples/core/example_30.rddl");
+test_inconsistent!(test_core_31, "tests/examples/core/example_31.rddl");
+test_chronoxide!(test_core_32, "tests/examples/core/example_32.rddl");
+test_chronoxide!(test_core_33, "tests/examples/core/example_33.rddl");
+test_inconsistent!(test_core_34, "tests/examples/core/example_34.rddl");
+test_chronoxide!(test_core_35, "tests/examples/core/example_35.rddl");
+test_chronoxide!(test_core_36, "tests/examples/core/example_36.rddl");
+test_inconsistent!(test_core_37, "tests/examples/core/example_37.rddl");
+test_chronoxide!(test_core_38, "tests/examples/core/example_38.rddl");
+test_inconsistent!(test_core_39, "tests/examples/core/example_39.rddl");
+test_inconsistent!(test_core_40, "tests/examples/core/example_40.rddl");
+test_inconsistent!(test_core_41, "tests/examples/core/example_41.rddl");
+test_inconsistent!(test_core_45, "tests/examples/core/example_45.rddl");
+test_inconsistent!(test_core_46, "tests/examples/core/example_46.rddl");
+
+async fn read_problem(solver: &Solver, path: &str) {
+    let mut full_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    full_path.push(path);
+    let content = read_to_string(&full_path).expect(&format!("Failed to read file: {}", path));
+    solver.read(content).await.expect("Failed to read problem");
+}
+
+/// Solves the given problem, returning the solver along with the atoms of its solution.
+async fn solve_problem(path: &str) -> (Solver, Vec<AtomSolution>) {
+    let solver = Solver::new();
+    read_problem(&solver, path).await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let atoms = solver.solution().await.expect("Failed to extract the solution").atoms;
+    (solver, atoms)
+}
+
+fn arith_arg(atom: &AtomSolution, name: &str) -> Rational {
+    match atom.args.get(name) {
+        Some(VarValue::Arith { val, .. }) => *val,
+        val => panic!("Expected an arithmetic value for `{}`, got {:?}", name, val),
+    }
+}
+
+async fn arith_value(solver: &Solver, path: &str) -> Rational {
+    match solver.value_of(path).await.expect(&format!("Failed to get the value of `{}`", path)) {
+        VarValue::Arith { val, .. } => val,
+        val => panic!("Expected an arithmetic value for `{}`, got {:?}", path, val),
+    }
+}
+
+/// Delegates to the given flaw selector, recording the candidates of each selection along with the selected flaw.
+struct RecordingSelector<S: FlawSelector> {
+    selector: S,
+    selections: Arc<Mutex<Vec<(Vec<FlawInfo>, FlawId)>>>,
+}
+
+impl<S: FlawSelector> FlawSelector for RecordingSelector<S> {
+    fn select_flaw(&mut self, flaws: &[FlawInfo]) -> Option<FlawId> {
+        let selected = self.selector.select_flaw(flaws)?;
+        self.selections.lock().unwrap().push((flaws.to_vec(), selected));
+        Some(selected)
+    }
+}
+
+/// Solves the problem with the given flaw selector, returning the candidates of each selection along with the selected flaw.
+async fn record_selections(selector: impl FlawSelector + 'static, path: &str) -> Vec<(Vec<FlawInfo>, FlawId)> {
+    let solver = Solver::new();
+    let selections = Arc::new(Mutex::new(Vec::new()));
+    solver.set_flaw_selector(RecordingSelector { selector, selections: selections.clone() }).await.expect("Failed to set the flaw selector");
+    read_problem(&solver, path).await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let selections = selections.lock().unwrap().clone();
+    assert!(!selections.is_empty(), "Expected the selector to be asked for a flaw");
+    selections
+}
+
+#[tokio::test]
+async fn test_fail_first_selector() {
+    for (flaws, selected) in record_selections(FailFirst, "tests/examples/core/example_07.rddl").await {
+        let fewest = flaws.iter().map(|flaw| flaw.live_resolvers).min().unwrap();
+        assert!(flaws.iter().any(|flaw| flaw.id == selected && flaw.live_resolvers == fewest), "Expected the flaw with the fewest live resolvers to be selected");
+    }
+}
+
+#[tokio::test]
+async fn test_fifo_selector() {
+    for (flaws, selected) in record_selections(Fifo, "tests/examples/core/example_07.rddl").await {
+        assert_eq!(selected, flaws[0].id, "Expected the oldest flaw to be selected");
+    }
+}
+
+#[tokio::test]
+async fn test_most_expensive_flaw_selector() {
+    for (flaws, selected) in record_selections(MostExpensiveFlaw, "tests/examples/core/example_09.rddl").await {
+        let highest = flaws.iter().map(|flaw| flaw.cost).max().unwrap();
+        assert!(flaws.iter().any(|flaw| flaw.id == selected && flaw.cost == highest), "Expected the most expensive flaw to be selected");
+    }
+}
+
+/// Solves the problem by deciding the object variables in creation order, returning the objects assigned to `x0` and `x1`.
+async fn ordered_values(ordering: fn(i32, i32) -> Ordering) -> (Vec<usize>, Vec<usize>) {
+    let solver = Solver::new();
+    solver.set_flaw_selector(Fifo).await.expect("Failed to set the flaw selector");
+    solver.set_resolver_selector(ValueOrdering::new(ordering)).await.expect("Failed to set the resolver selector");
+    read_problem(&solver, "tests/examples/core/example_07.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    match (solver.value_of("x0").await.expect("Failed to get the value of `x0`"), solver.value_of("x1").await.expect("Failed to get the value of `x1`")) {
+        (VarValue::Enum(x0), VarValue::Enum(x1)) => (x0, x1),
+        vals => panic!("Expected object values, got {:?}", vals),
+    }
+}
+
+#[tokio::test]
+async fn test_value_ordering_selector() {
+    // `x0` is decided first, hence it takes the first of its allowed values according to the ordering
+    let (x0, x1) = ordered_values(|a, b| a.cmp(&b)).await;
+    assert!(x0.len() == 1 && x1.len() == 1 && x0[0] < x1[0], "Expected `x0` to take the lowest value, got {:?} and {:?}", x0, x1);
+    let (x0, x1) = ordered_values(|a, b| b.cmp(&a)).await;
+    assert!(x0.len() == 1 && x1.len() == 1 && x0[0] > x1[0], "Expected `x0` to take the highest value, got {:?} and {:?}", x0, x1);
+}
+
+#[tokio::test]
+async fn test_additive_cost_heuristic() {
+    let solver = Solver::new();
+    solver.set_cost_heuristic(CostHeuristic::Add).await.expect("Failed to set the cost heuristic");
+    read_problem(&solver, "tests/examples/core/example_09.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+}
+
+/// Returns the highest cost among the flaws the search starts from, when estimated through the given heuristic.
+async fn initial_cost(heuristic: CostHeuristic) -> Rational {
+    let solver = Solver::new();
+    let selections = Arc::new(Mutex::new(Vec::new()));
+    solver.set_flaw_selector(RecordingSelector { selector: MostExpensiveFlaw, selections: selections.clone() }).await.expect("Failed to set the flaw selector");
+    solver.set_cost_heuristic(heuristic).await.expect("Failed to set the cost heuristic");
+    read_problem(&solver, "tests/examples/core/example_09.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let selections = selections.lock().unwrap();
+    selections.first().expect("Expected the selector to be asked for a flaw").0.iter().map(|flaw| flaw.cost).max().unwrap()
+}
+
+#[tokio::test]
+async fn test_cost_heuristics_estimates() {
+    // each `Q` goal requires two `P` subgoals, whose costs h_add and h_ff add up while h_max only takes the highest
+    let max = initial_cost(CostHeuristic::Max).await;
+    assert!(initial_cost(CostHeuristic::Add).await > max, "Expected h_add to exceed h_max");
+    assert!(initial_cost(CostHeuristic::FastForward).await > max, "Expected h_ff to exceed h_max");
+}
+
+#[tokio::test]
+async fn test_fast_forward_cost_heuristic() {
+    let solver = Solver::new();
+    solver.set_cost_heuristic(CostHeuristic::FastForward).await.expect("Failed to set the cost heuristic");
+    read_problem(&solver, "tests/examples/core/example_09.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+}
+
+#[tokio::test]
+async fn test_statistics() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_07.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let statistics = solver.statistics().await.expect("Failed to get the statistics");
+    assert_eq!(statistics.flaws.get(&FlawKind::Enum), Some(&3));
+    assert!(statistics.decisions > 0);
+    assert!(statistics.resolvers >= 9);
+}
+
+#[tokio::test]
+async fn test_statistics_per_solve() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_07.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let first = solver.statistics().await.expect("Failed to get the statistics");
+    assert!(first.decisions > 0);
+    solver.solve().await.expect("Failed to solve the problem again");
+    let second = solver.statistics().await.expect("Failed to get the statistics");
+    assert_eq!(second.flaws.get(&FlawKind::Enum), None, "Expected the flaws of the first solve not to be reported again");
+    assert_eq!(second.decisions, 0, "Expected the solved problem to require no further decisions");
+}
+
+#[tokio::test]
+async fn test_cancelled_solve() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_07.rddl").await;
+    let cancellation = CancellationHandle::new();
+    cancellation.cancel();
+    let result = solver.solve_with_limits(SolveLimits { cancellation: Some(cancellation), ..Default::default() }).await;
+    assert!(matches!(result, Err(SolverError::Cancelled)), "Expected the solve to be cancelled");
+    solver.to_json().await.expect("Failed to inspect the solver after cancellation");
+}
+
+#[tokio::test]
+async fn test_flaw_budget() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_07.rddl").await;
+    let result = solver.solve_with_limits(SolveLimits { max_flaws: Some(1), ..Default::default() }).await;
+    assert!(matches!(result, Err(SolverError::Timeout)), "Expected the solve to exhaust its budget");
+}
+
+#[tokio::test]
+async fn test_flaw_budget_per_solve() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_07.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    // the flaws of the first solve do not count against the budget of the second one
+    solver.solve_with_limits(SolveLimits { max_flaws: Some(0), max_resolvers: Some(0), ..Default::default() }).await.expect("Failed to solve the problem again");
+}
+
+#[tokio::test]
+async fn test_solution() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_09.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let solution = solver.solution().await.expect("Failed to extract the solution");
+    assert_eq!(solution.atoms.iter().filter(|atom| atom.predicate == "Q").count(), 2);
+    assert!(solution.atoms.iter().any(|atom| atom.predicate == "P"));
+    assert!(matches!(solution.globals.get("n"), Some(VarValue::Arith { .. })));
+    assert!(!solution.globals.contains_key("origin") && !solution.globals.contains_key("horizon"), "Expected the prelude variables not to be part of the solution");
+}
+
+#[tokio::test]
+async fn test_core_11() {
+    let (_, atoms) = solve_problem("tests/examples/core/example_11.rddl").await;
+    assert_eq!(atoms.len(), 2);
+    let (a, b) = (&atoms[0], &atoms[1]);
+    assert!(arith_arg(a, "end") <= arith_arg(b, "start") || arith_arg(b, "end") <= arith_arg(a, "start"), "Expected the atoms of the state variable not to overlap");
+}
+
+#[tokio::test]
+async fn test_core_13() {
+    let (solver, atoms) = solve_problem("tests/examples/core/example_13.rddl").await;
+    let capacity = arith_value(&solver, "crane.capacity").await;
+    // the usage peaks when some use starts
+    for atom in atoms.iter() {
+        let time = arith_arg(atom, "start");
+        let usage = atoms.iter().filter(|other| arith_arg(other, "start") <= time && time < arith_arg(other, "end")).fold(Rational::from(0), |sum, other| sum + arith_arg(other, "amount"));
+        assert!(usage <= capacity, "Expected the usage at {:?} to be within the capacity, got {:?}", time, usage);
+    }
+}
+
+/// Solves the given problem, checking that the level of the given consumable resource stays within its bounds.
+async fn assert_levels(path: &str, resource: &str) {
+    let (solver, atoms) = solve_problem(path).await;
+    let initial = arith_value(&solver, &format!("{}.initial_amount", resource)).await;
+    let min = arith_value(&solver, &format!("{}.min_amount", resource)).await;
+    let max = arith_value(&solver, &format!("{}.max_amount", resource)).await;
+    // productions take effect at their end, consumptions at their start
+    let mut changes = atoms
+        .iter()
+        .filter_map(|atom| match atom.predicate.as_str() {
+            "Produce" => Some((arith_arg(atom, "end"), arith_arg(atom, "amount"))),
+            "Consume" => Some((arith_arg(atom, "start"), -arith_arg(atom, "amount"))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(!changes.is_empty(), "Expected the resource to be changed");
+    changes.sort_by_key(|(time, _)| *time);
+    let mut level = initial;
+    for (i, (time, amount)) in changes.iter().enumerate() {
+        level = level + *amount;
+        if i + 1 < changes.len() && changes[i + 1].0 == *time {
+            continue;
+        }
+        assert!(min <= level && level <= max, "Expected the level at {:?} to be within [{:?}, {:?}], got {:?}", time, min, max, level);
+    }
+}
+
+#[tokio::test]
+async fn test_core_14() {
+    assert_levels("tests/examples/core/example_14.rddl", "battery").await;
+}
+
+#[tokio::test]
+async fn test_core_42() {
+    assert_levels("tests/examples/core/example_42.rddl", "battery").await;
+}
+
+/// Solves the given problem, checking that the overlapping positive `At` atoms are the same fluent, and that no negative one overlaps a positive one of the same fluent.
+async fn assert_mutual_exclusion(path: &str) {
+    let (_, atoms) = solve_problem(path).await;
+    let location = |atom: &AtomSolution| match atom.args.get("l") {
+        Some(VarValue::Object(id)) => *id,
+        Some(VarValue::Enum(ids)) if ids.len() == 1 => ids[0],
+        val => panic!("Expected a single location, got {:?}", val),
+    };
+    let polarity = |atom: &AtomSolution| match atom.args.get("polarity") {
+        Some(VarValue::Bool(val)) => *val == LBool::True,
+        val => panic!("Expected a boolean value, got {:?}", val),
+    };
+    let fluents = atoms.iter().filter(|atom| atom.predicate == "At").collect::<Vec<_>>();
+    assert!(fluents.len() > 1, "Expected several fluents");
+    for (i, a) in fluents.iter().enumerate() {
+        for b in fluents.iter().skip(i + 1) {
+            if arith_arg(a, "end") <= arith_arg(b, "start") || arith_arg(b, "end") <= arith_arg(a, "start") {
+                continue;
+            }
+            match (polarity(a), polarity(b)) {
+                (true, true) => assert_eq!(location(a), location(b), "Expected the overlapping atoms {} and {} to be the same fluent", a.id, b.id),
+                (false, false) => {}
+                _ => assert_ne!(location(a), location(b), "Expected the atoms {} and {} of opposite polarities not to overlap", a.id, b.id),
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_core_18() {
+    assert_mutual_exclusion("tests/examples/core/example_18.rddl").await;
+}
+
+#[tokio::test]
+async fn test_core_43() {
+    assert_mutual_exclusion("tests/examples/core/example_43.rddl").await;
+}
+
+#[tokio::test]
+async fn test_core_25() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_25.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let val = |val: VarValue| match val {
+        VarValue::Arith { val, .. } => val,
+        val => panic!("Expected an arithmetic value, got {:?}", val),
+    };
+    let n: Rational = val(solver.value_of("n").await.expect("Failed to get the value of `n`"));
+    let m: Rational = val(solver.value_of("m").await.expect("Failed to get the value of `m`"));
+    assert_eq!(n.floor(), n, "Expected `n` to be an integer");
+    assert_eq!(Rational::from(2) * n, Rational::from(5) * m, "Expected `2n == 5m`");
+}
+
+#[tokio::test]
+async fn test_interval_bounds() {
+    let solver = Solver::new();
+    solver.read("fact f0 = new Interval(start:2.0, duration:3.0);\nfact f1 = new Interval(start:1.0, end:4.0);".to_string()).await.expect("Failed to read problem");
+    solver.solve().await.expect("Failed to solve the problem");
+    let solution = solver.solution().await.expect("Failed to extract the solution");
+    let arg = |atom: usize, name: &str| match solution.atoms[atom].args.get(name) {
+        Some(VarValue::Arith { val, .. }) => *val,
+        val => panic!("Expected an arithmetic value, got {:?}", val),
+    };
+    assert_eq!(arg(0, "end"), Rational::from(5));
+    assert_eq!(arg(1, "duration"), Rational::from(3));
+}
+
+#[tokio::test]
+async fn test_value_of() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_04.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    match solver.value_of("b3.weight").await.expect("Failed to get the value of `b3.weight`") {
+        VarValue::Arith { lb, ub, .. } => assert!(lb == ub, "Expected `b3.weight` to be constant"),
+        val => panic!("Expected an arithmetic value, got {:?}", val),
+    }
+    match solver.value_of("b").await.expect("Failed to get the value of `b`") {
+        VarValue::Enum(vals) => assert_eq!(vals.len(), 1),
+        val => panic!("Expected an object variable, got {:?}", val),
+    }
+    assert!(solver.value_of("b.weight.foo").await.is_err());
+}
+
+#[tokio::test]
+async fn test_difference_constraints() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_20.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let val = |val: VarValue| match val {
+        VarValue::Arith { lb, ub, val } => {
+            assert!(lb <= val && val <= ub, "Expected the value to be within its bounds");
+            val
+        }
+        val => panic!("Expected an arithmetic value, got {:?}", val),
+    };
+    let a = val(solver.value_of("a").await.expect("Failed to get the value of `a`"));
+    let c = val(solver.value_of("c").await.expect("Failed to get the value of `c`"));
+    assert!(c - a >= Rational::from(6), "Expected the second disjunct to hold");
+}
+
+#[tokio::test]
+async fn test_element_bounds() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_32.rddl").await;
+    // the bound on the weight prunes `b0`, while removing `b2` tightens the weight
+    let b1 = match solver.value_of("b1").await.expect("Failed to get the value of `b1`") {
+        VarValue::Object(id) => id,
+        val => panic!("Expected an object, got {:?}", val),
+    };
+    assert_eq!(solver.value_of("b").await.expect("Failed to get the value of `b`"), VarValue::Enum(vec![b1]));
+    match solver.value_of("b.weight").await.expect("Failed to get the value of `b.weight`") {
+        VarValue::Arith { lb, ub, .. } => assert!(lb == Rational::from(2) && ub == Rational::from(2), "Expected the weight of `b1`, got [{:?}, {:?}]", lb, ub),
+        val => panic!("Expected an arithmetic value, got {:?}", val),
+    }
+    solver.solve().await.expect("Failed to solve the problem");
+    match solver.value_of("b.weight").await.expect("Failed to get the value of `b.weight`") {
+        VarValue::Arith { lb, ub, val } => assert!(lb == ub && lb == val && val == Rational::from(2), "Expected the weight of `b1`"),
+        val => panic!("Expected an arithmetic value, got {:?}", val),
+    }
+}
+
+#[tokio::test]
+async fn test_string_vars() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_33.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    match solver.value_of("mode").await.expect("Failed to get the value of `mode`") {
+        VarValue::String(mode) => assert_eq!(mode, "maintenance"),
+        val => panic!("Expected a string value, got {:?}", val),
+    }
+    assert!(matches!(solver.value_of("other").await.expect("Failed to get the value of `other`"), VarValue::String(other) if other == "busy"));
+    let statistics = solver.statistics().await.expect("Failed to get the statistics");
+    assert_eq!(statistics.flaws.get(&FlawKind::String), Some(&2));
+    assert_eq!(statistics.flaws.get(&FlawKind::Enum), None);
+}
+
+#[tokio::test]
+async fn test_string_literals_read_later() {
+    let solver = Solver::new();
+    solver.read("string mode;".to_string()).await.expect("Failed to read problem");
+    solver.read("mode == \"busy\";".to_string()).await.expect("Failed to read problem");
+    solver.solve().await.expect("Failed to solve the problem");
+    assert!(matches!(solver.value_of("mode").await.expect("Failed to get the value of `mode`"), VarValue::String(mode) if mode == "busy"));
+}
+
+#[tokio::test]
+async fn test_bool_vars() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_35.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    for name in ["b0", "b1", "b2"] {
+        match solver.value_of(name).await.expect("Failed to get the value of a Boolean variable") {
+            VarValue::Bool(val) => assert_ne!(val, LBool::Undef, "Expected `{}` to be decided", name),
+            val => panic!("Expected a Boolean value, got {:?}", val),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_products() {
+    let solver = Solver::new();
+    solver.set_product_tolerance(Rational::new(1, 10000)).await.expect("Failed to set the product tolerance");
+    read_problem(&solver, "tests/examples/core/example_36.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let val = |val: VarValue| match val {
+        VarValue::Arith { val, .. } => val,
+        val => panic!("Expected an arithmetic value, got {:?}", val),
+    };
+    let power = val(solver.value_of("power").await.expect("Failed to get the value of `power`"));
+    let duration = val(solver.value_of("duration").await.expect("Failed to get the value of `duration`"));
+    let energy = val(solver.value_of("energy").await.expect("Failed to get the value of `energy`"));
+    let error = energy - power * duration;
+    assert!(-Rational::new(1, 10000) <= error && error <= Rational::new(1, 10000), "Expected the product to be exact within the tolerance");
+}
+
+#[tokio::test]
+async fn test_product_bounded_later() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_44.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let val = |val: VarValue| match val {
+        VarValue::Arith { val, .. } => val,
+        val => panic!("Expected an arithmetic value, got {:?}", val),
+    };
+    let power = val(solver.value_of("power").await.expect("Failed to get the value of `power`"));
+    let duration = val(solver.value_of("duration").await.expect("Failed to get the value of `duration`"));
+    let energy = val(solver.value_of("energy").await.expect("Failed to get the value of `energy`"));
+    let error = energy - power * duration;
+    assert!(-Rational::new(1, 1000) <= error && error <= Rational::new(1, 1000), "Expected the product to be exact within the tolerance");
+}
+
+#[tokio::test]
+async fn test_unbounded_product() {
+    let solver = Solver::new();
+    solver.read("real x, y, z;\nx >= 1.0;\nz == x * y;\nz >= 1.0;".to_string()).await.expect("Failed to read problem");
+    assert!(solver.solve().await.is_err(), "Expected the product of unbounded factors to be rejected");
+}
+
+#[tokio::test]
+async fn test_capacity_of_object_variable() {
+    let solver = Solver::new();
+    read_problem(&solver, "tests/examples/core/example_47.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let big = match solver.value_of("big").await.expect("Failed to get the value of `big`") {
+        VarValue::Object(id) => id,
+        val => panic!("Expected an object, got {:?}", val),
+    };
+    assert_eq!(solver.value_of("crane").await.expect("Failed to get the value of `crane`"), VarValue::Enum(vec![big]));
+}
+
+#[tokio::test]
+async fn test_jointly_infeasible_unifications() {
+    let solver = Solver::new();
+    solver.set_resolver_selector(PreferUnification).await.expect("Failed to set the resolver selector");
+    read_problem(&solver, "tests/examples/core/example_38.rddl").await;
+    solver.solve().await.expect("Failed to solve the problem");
+    let val = |val: VarValue| match val {
+        VarValue::Arith { val, .. } => val,
+        val => panic!("Expected an arithmetic value, got {:?}", val),
+    };
+    let a = val(solver.value_of("a").await.expect("Failed to get the value of `a`"));
+    let b = val(solver.value_of("b").await.expect("Failed to get the value of `b`"));
+    assert_eq!(a + b, Rational::from(5));
+    let statistics = solver.statistics().await.expect("Failed to get the statistics");
+    assert!(statistics.conflicts > 0, "Expected the second unification to conflict");
+    assert!(statistics.learnts > 0, "Expected the conflict to be learnt as a no-good");
+    // the no-good excludes unifying both goals
+    let solution = solver.solution().await.expect("Failed to extract the solution");
+    assert_eq!(solution.unifications.len(), 1, "Expected a single goal to be unified");
+}